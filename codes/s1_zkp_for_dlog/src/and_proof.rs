@@ -0,0 +1,300 @@
+//! AND-composition: knowledge of every `x_i` with `y_i = g_i^x_i` in one proof.
+//!
+//! The Schnorr proof run once per statement, with a single challenge `c` hashed from all the
+//! bases, residues and commitments: branch `i` commits to `h_i = g_i^r_i` and answers with
+//! `s_i = r_i + c * x_i (mod order)`. Since one `c` binds every branch, the proof only
+//! verifies if the prover knows all the secrets at once.
+
+use crypto_bigint::rand_core::{CryptoRngCore, OsRng};
+
+use crate::{
+    check_bases, Error, Group, PublicParams, SchnorrProof, Statement, Transcript, Witness,
+};
+
+// protocol label absorbed first into the transcript of the and-proof
+const PROTOCOL_LABEL: &[u8] = b"dlog-and";
+
+/// One [`SchnorrProof`] per statement sharing the challenge, `h` is committed to the base `g_i`
+pub type AndProof<G> = Vec<SchnorrProof<G>>;
+
+/// Prove that we know every x_i such that y_i = g_i^x_i, with `bases[i]` as g_i
+///
+/// Returns (statements y_i, proof), or [`Error::WrongBranchCount`] if there is not exactly
+/// one base per witness.
+pub fn prove_and<G: Group>(
+    params: &PublicParams<G>,
+    bases: &[G::Element],
+    witnesses: &[Witness<G>],
+) -> Result<(Vec<Statement<G>>, AndProof<G>), Error> {
+    prove_and_with_context(params, bases, witnesses, &[])
+}
+
+/// Same as [`prove_and`], with the proof bound to a caller chosen `context`
+pub fn prove_and_with_context<G: Group>(
+    params: &PublicParams<G>,
+    bases: &[G::Element],
+    witnesses: &[Witness<G>],
+    context: &[u8],
+) -> Result<(Vec<Statement<G>>, AndProof<G>), Error> {
+    prove_and_with_rng(params, bases, witnesses, context, &mut OsRng)
+}
+
+/// Same as [`prove_and_with_context`], drawing the nonces from `rng` instead of the OS
+pub fn prove_and_with_rng<G: Group>(
+    params: &PublicParams<G>,
+    bases: &[G::Element],
+    witnesses: &[Witness<G>],
+    context: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<(Vec<Statement<G>>, AndProof<G>), Error> {
+    if witnesses.len() != bases.len() {
+        return Err(Error::WrongBranchCount {
+            expected: bases.len(),
+            found: witnesses.len(),
+        });
+    }
+
+    let group = params.group();
+
+    // y_i = g_i^x_i
+    let statements: Vec<_> = bases
+        .iter()
+        .zip(witnesses)
+        .map(|(base, witness)| Statement::new(group.exp(base, witness.expose_secret())))
+        .collect();
+
+    // commit to every h_i = g_i^r_i before the shared challenge exists
+    let nonces: Vec<_> = bases.iter().map(|_| group.random_scalar(rng)).collect();
+    let commitments: Vec<_> = bases
+        .iter()
+        .zip(&nonces)
+        .map(|(base, r)| group.exp(base, r))
+        .collect();
+
+    // s_i = r_i + c * x_i (mod order)
+    let c = get_challenge_by_hashing(group, bases, &statements, context, &commitments);
+    let proof = nonces
+        .into_iter()
+        .zip(commitments)
+        .zip(witnesses)
+        .map(|((r, h), witness)| {
+            let s = group.scalar_add(&r, &group.scalar_mul(&c, witness.expose_secret()));
+
+            SchnorrProof { h, s }
+        })
+        .collect();
+
+    Ok((statements, proof))
+}
+
+/// Evaluates to Ok if `proof` shows knowledge of every x_i such that y_i = g_i^x_i
+///
+/// Errors of a statement are reported with its index as the round.
+pub fn verify_and<G: Group>(
+    params: &PublicParams<G>,
+    bases: &[G::Element],
+    statements: &[Statement<G>],
+    proof: AndProof<G>,
+) -> Result<(), Error> {
+    verify_and_with_context(params, bases, statements, &[], proof)
+}
+
+/// Same as [`verify_and`], for proofs made by [`prove_and_with_context`]
+pub fn verify_and_with_context<G: Group>(
+    params: &PublicParams<G>,
+    bases: &[G::Element],
+    statements: &[Statement<G>],
+    context: &[u8],
+    proof: AndProof<G>,
+) -> Result<(), Error> {
+    if statements.len() != bases.len() {
+        return Err(Error::WrongBranchCount {
+            expected: bases.len(),
+            found: statements.len(),
+        });
+    }
+    if proof.len() != statements.len() {
+        return Err(Error::WrongBranchCount {
+            expected: statements.len(),
+            found: proof.len(),
+        });
+    }
+
+    let group = params.group();
+    check_bases(group, bases)?;
+    if !statements
+        .iter()
+        .all(|statement| group.contains(&statement.residue()))
+    {
+        return Err(Error::ResidueOutOfRange);
+    }
+    if let Some(round) = proof.iter().position(|branch| !group.contains(&branch.h)) {
+        return Err(Error::NonCanonicalCommitment { round });
+    }
+    if let Some(round) = proof
+        .iter()
+        .position(|branch| !group.is_canonical_scalar(&branch.s))
+    {
+        return Err(Error::NonCanonicalResponse { round });
+    }
+
+    let commitments: Vec<_> = proof.iter().map(|branch| branch.h).collect();
+    let c = get_challenge_by_hashing(group, bases, statements, context, &commitments);
+
+    for (round, ((base, statement), SchnorrProof { h, s })) in
+        bases.iter().zip(statements).zip(proof).enumerate()
+    {
+        let lhs = group.exp(base, &s); // g_i ^ s_i
+        let rhs = group.operate(&h, &group.exp(&statement.residue(), &c)); // h_i * y_i ^ c
+
+        if lhs != rhs {
+            return Err(Error::RoundFailed { round });
+        }
+    }
+
+    Ok(())
+}
+
+/// Derive the shared challenge `c` from a transcript over every statement and every commitment
+fn get_challenge_by_hashing<G: Group>(
+    group: &G,
+    bases: &[G::Element],
+    statements: &[Statement<G>],
+    context: &[u8],
+    commitments: &[G::Element],
+) -> G::Scalar {
+    let mut transcript = Transcript::new(PROTOCOL_LABEL);
+    transcript.append_message(b"group", &group.encode_parameters());
+    transcript.append_element(b"g", group, &group.generator());
+    transcript.append_message(b"n", &(statements.len() as u64).to_be_bytes());
+    for (base, statement) in bases.iter().zip(statements) {
+        transcript.append_element(b"g_i", group, base);
+        transcript.append_element(b"y_i", group, &statement.residue());
+    }
+    transcript.append_message(b"context", context);
+    for h in commitments {
+        transcript.append_element(b"h", group, h);
+    }
+
+    group.challenge_scalar(&mut transcript, b"c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        group::standard::SECP256K1,
+        test_util::{large_params, params, prove_seeded},
+    };
+    use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn test_positive() {
+        let params = params(47, 23, 2);
+        let group = params.group();
+        let bases = [2, 3, 7].map(|k| group.exp_generator(&U256::from_u64(k)));
+        let witnesses = [5, 17, 9].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let (statements, proof) = prove_and(&params, &bases, &witnesses).unwrap();
+
+        assert_eq!(
+            statements[1].residue(),
+            group.exp(&bases[1], &U256::from_u64(17))
+        );
+        assert_eq!(verify_and(&params, &bases, &statements, proof), Ok(()));
+    }
+
+    #[test]
+    fn test_negative() {
+        let params = large_params();
+        let group = params.group();
+        let bases = [group.generator(); 3];
+        let witnesses = [5, 17, 9].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let (mut statements, proof) = prove_and(&params, &bases, &witnesses).unwrap();
+
+        // knowing two secrets out of three is not enough
+        statements[2] =
+            Statement::from_witness(&params, &Witness::new(&params, U256::from_u64(10)));
+        assert!(matches!(
+            verify_and(&params, &bases, &statements, proof.clone()),
+            Err(Error::RoundFailed { .. })
+        ));
+
+        assert_eq!(
+            verify_and(&params, &bases[..2], &statements[..2], proof),
+            Err(Error::WrongBranchCount {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_wrong_base_count() {
+        let params = params(59, 29, 4);
+        let bases = [params.group().generator(); 2];
+        let witnesses = [3, 10].map(|x| Witness::new(&params, U256::from_u64(x)));
+
+        assert_eq!(
+            prove_and(&params, &bases[..1], &witnesses),
+            Err(Error::WrongBranchCount {
+                expected: 1,
+                found: 2
+            })
+        );
+
+        let (statements, proof) = prove_and(&params, &bases, &witnesses).unwrap();
+        assert_eq!(
+            verify_and(&params, &bases[..1], &statements, proof),
+            Err(Error::WrongBranchCount {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_degenerate_base() {
+        let params = params(59, 29, 4);
+        let bases = [params.group().generator(), params.group().identity()];
+        let witnesses = [3, 10].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let (statements, proof) = prove_and(&params, &bases, &witnesses).unwrap();
+
+        assert_eq!(
+            verify_and(&params, &bases, &statements, proof),
+            Err(Error::BaseOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_ordered_branches() {
+        let params = PublicParams::new_unchecked(SECP256K1.curve().unwrap());
+        let group = params.group();
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let bases = [
+            group.generator(),
+            group.exp_generator(&group.random_scalar(&mut rng)),
+        ];
+        let witnesses = [(); 2].map(|_| Witness::random(&params, &mut rng));
+        let (statements, proof) = prove_seeded(|rng| {
+            prove_and_with_rng(&params, &bases, &witnesses, b"alice", rng).unwrap()
+        });
+        assert_eq!(
+            verify_and_with_context(&params, &bases, &statements, b"alice", proof.clone()),
+            Ok(())
+        );
+        assert_eq!(
+            verify_and_with_context(&params, &bases, &statements, b"bob", proof.clone()),
+            Err(Error::RoundFailed { round: 0 })
+        );
+
+        // swapping the (base, statement, branch) triples changes the shared c
+        let bases: Vec<_> = bases.into_iter().rev().collect();
+        let statements: Vec<_> = statements.into_iter().rev().collect();
+        let proof: Vec<_> = proof.into_iter().rev().collect();
+        assert_eq!(
+            verify_and_with_context(&params, &bases, &statements, b"alice", proof),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+}
@@ -0,0 +1,663 @@
+//! `zkp-dlog`, command-line front end for proving and verifying knowledge of a discrete log.
+//!
+//! ```text
+//! zkp-dlog keygen [--group NAME | --bits N] [PARAMETERS]   new secret x and residue y = g^x
+//! zkp-dlog public [KEY]                                    public part of a key, without x
+//! zkp-dlog prove [--context TEXT] [--soundness BITS] [KEY]     proof for a key made by keygen
+//! zkp-dlog verify --key PUBLIC [--context TEXT] [--soundness BITS] [PROOF]
+//!                                                          check a proof for the key PUBLIC
+//! zkp-dlog inspect [FILE]                                  describe parameters, a key or a proof
+//! ```
+//!
+//! Inputs are read from the named file, or from stdin when it is missing or `-`.
+//! verify only accepts a proof about the parameters and residue of the key it is given
+//! (a key or its public part), under the context it is given (empty by default): the ones
+//! inside a proof are chosen by whoever made it. inspect only checks a proof against its own.
+//! Results go to stdout. `NAME` is one of `modp2048`, `modp3072`, `modp4096`, `ffdhe2048`,
+//! `ffdhe3072`, `ffdhe4096`, `ffdhe6144` or `ffdhe8192`; without `--group` and `--bits`
+//! keygen reads parameters from its input. `--soundness` is the number of bits of soundness
+//! the proof is made with, or the verifier requires at least, 100 by default and never 0.
+//!
+//! # Format
+//!
+//! Every document is JSON, every integer a fixed-width big-endian hex string as in
+//! [`s1_zkp_for_dlog::encoding`]. The width (256, 512, 1024, 2048, 3072, 4096, 6144 or 8192
+//! bits) is the same for every integer of a document.
+//!
+//! ```text
+//! parameters: {"modulus": p, "order": q, "generator": g}
+//! key:        {"parameters": parameters, "residue": y, "secret": x}
+//! public key: {"parameters": parameters, "residue": y}
+//! proof:      {"parameters": parameters, "residue": y, "context": hex bytes,
+//!              "proofs": [{"h": h, "s": s}, ...]}
+//! ```
+//!
+//! # Exit status
+//!
+//! 0 on success, 1 when a proof is rejected, 2 on any other error (usage, IO, malformed input).
+
+use std::{
+    fmt, fs,
+    io::{self, Read},
+    process::ExitCode,
+};
+
+use crypto_bigint::{rand_core::OsRng, Uint, U1024, U2048, U256, U3072, U4096, U512, U6144, U8192};
+use s1_zkp_for_dlog::{
+    generate_parameters,
+    group::standard::{
+        FFDHE2048, FFDHE3072, FFDHE4096, FFDHE6144, FFDHE8192, MODP_2048, MODP_3072, MODP_4096,
+    },
+    prove_with_rounds, rounds_for_soundness,
+    serialization::fixed_bytes,
+    verify_with_min_rounds, Error, ModpGroup, ModpParameters, Proofs, PublicParams, Statement,
+    Witness, ROUND_OF_VERIFY,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroize;
+
+const USAGE: &str = "usage: zkp-dlog keygen [--group NAME | --bits N] [PARAMETERS]
+       zkp-dlog public [KEY]
+       zkp-dlog prove [--context TEXT] [--soundness BITS] [KEY]
+       zkp-dlog verify --key PUBLIC [--context TEXT] [--soundness BITS] [PROOF]
+       zkp-dlog inspect [FILE]";
+
+// widths integers can be encoded with, in bits
+const WIDTHS: [usize; 8] = [256, 512, 1024, 2048, 3072, 4096, 6144, 8192];
+
+// run `$function::<LIMBS>(...)` for the LIMBS of a width in bits
+macro_rules! dispatch {
+    ($width:expr, $function:ident($($argument:expr),*)) => {
+        match $width {
+            256 => $function::<{ U256::LIMBS }>($($argument),*),
+            512 => $function::<{ U512::LIMBS }>($($argument),*),
+            1024 => $function::<{ U1024::LIMBS }>($($argument),*),
+            2048 => $function::<{ U2048::LIMBS }>($($argument),*),
+            3072 => $function::<{ U3072::LIMBS }>($($argument),*),
+            4096 => $function::<{ U4096::LIMBS }>($($argument),*),
+            6144 => $function::<{ U6144::LIMBS }>($($argument),*),
+            8192 => $function::<{ U8192::LIMBS }>($($argument),*),
+            width => Err(Failure::Invalid(format!("unsupported width of {width} bits"))),
+        }
+    };
+}
+
+/// A secret key, zeroized on drop and never printed
+#[derive(PartialEq, Serialize, Deserialize)]
+struct Key<const LIMBS: usize> {
+    parameters: ModpParameters<LIMBS>,
+    residue: Statement<ModpGroup<LIMBS>>,
+    #[serde(with = "fixed_bytes")]
+    secret: Uint<LIMBS>,
+}
+
+impl<const LIMBS: usize> fmt::Debug for Key<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("parameters", &self.parameters)
+            .field("residue", &self.residue)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<const LIMBS: usize> Drop for Key<LIMBS> {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+/// A [`Key`] without its secret, what a verifier is given
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PublicKey<const LIMBS: usize> {
+    parameters: ModpParameters<LIMBS>,
+    residue: Statement<ModpGroup<LIMBS>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ProofDocument<const LIMBS: usize> {
+    parameters: ModpParameters<LIMBS>,
+    residue: Statement<ModpGroup<LIMBS>>,
+    #[serde(with = "hex")]
+    context: Vec<u8>,
+    proofs: Proofs<ModpGroup<LIMBS>>,
+}
+
+#[derive(Debug)]
+enum Failure {
+    /// The proof does not verify, exit status 1
+    Rejected(Error),
+    /// The proof is about other parameters or another residue than the key, exit status 1
+    WrongKey,
+    /// Anything else, exit status 2
+    Invalid(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rejected(error) => write!(f, "proof rejected: {error}"),
+            Self::WrongKey => f.write_str("proof rejected: not a proof for this key"),
+            Self::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Self {
+        Self::Invalid(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Failure {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Invalid(format!("malformed input: {error}"))
+    }
+}
+
+type Result<T> = std::result::Result<T, Failure>;
+
+/// Parsed command line, options may appear anywhere after the command
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    group: Option<String>,
+    bits: Option<usize>,
+    context: Option<String>,
+    soundness: Option<usize>,
+    key: Option<String>,
+    input: Option<String>,
+}
+
+impl Options {
+    /// Rounds to prove with, or to require at least when verifying
+    fn rounds(&self) -> usize {
+        self.soundness
+            .map(rounds_for_soundness)
+            .unwrap_or(ROUND_OF_VERIFY)
+    }
+}
+
+fn parse_options(arguments: &[String]) -> Result<Options> {
+    let mut options = Options::default();
+    let mut arguments = arguments.iter();
+
+    while let Some(argument) = arguments.next() {
+        let mut value = || {
+            arguments
+                .next()
+                .cloned()
+                .ok_or_else(|| Failure::Invalid(format!("missing value of {argument}")))
+        };
+
+        let mut number = || {
+            let value = value()?;
+            value
+                .parse()
+                .map_err(|_| Failure::Invalid(format!("invalid number of bits {value}")))
+        };
+
+        match argument.as_str() {
+            "--group" => options.group = Some(value()?),
+            "--bits" => options.bits = Some(number()?),
+            "--context" => options.context = Some(value()?),
+            "--soundness" => match number()? {
+                0 => return Err(Failure::Invalid("soundness of 0 bits".to_string())),
+                bits => options.soundness = Some(bits),
+            },
+            "--key" => options.key = Some(value()?),
+            option if option.starts_with("--") => {
+                return Err(Failure::Invalid(format!("unknown option {option}")))
+            }
+            _ if options.input.is_none() => options.input = Some(argument.clone()),
+            _ => return Err(Failure::Invalid(format!("unexpected argument {argument}"))),
+        }
+    }
+
+    Ok(options)
+}
+
+fn read_input(input: Option<&str>) -> Result<String> {
+    match input {
+        None | Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+        Some(path) => Ok(fs::read_to_string(path)?),
+    }
+}
+
+/// Width in bits of the integers of a document, taken from its modulus
+fn width_of(document: &Value) -> Result<usize> {
+    let parameters = document.get("parameters").unwrap_or(document);
+    let modulus = parameters
+        .get("modulus")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Failure::Invalid("input has no modulus".to_string()))?;
+
+    Ok(modulus.len() * 4)
+}
+
+fn keygen_with<const LIMBS: usize>(parameters: ModpParameters<LIMBS>) -> Result<String> {
+    let params = PublicParams::try_from(parameters)
+        .map_err(|error| Failure::Invalid(format!("invalid parameters: {error}")))?;
+
+    keygen_in(&params)
+}
+
+fn keygen_in<const LIMBS: usize>(params: &PublicParams<ModpGroup<LIMBS>>) -> Result<String> {
+    let witness = Witness::random(params, &mut OsRng);
+    let key = Key {
+        parameters: params.group().parameters(),
+        residue: Statement::from_witness(params, &witness),
+        secret: *witness.expose_secret(),
+    };
+
+    Ok(serde_json::to_string_pretty(&key)?)
+}
+
+fn keygen_generated<const LIMBS: usize>(bits: usize) -> Result<String> {
+    // generated parameters come validated
+    keygen_in(&generate_parameters::<LIMBS>(bits, &mut OsRng))
+}
+
+fn keygen_parsed<const LIMBS: usize>(document: Value) -> Result<String> {
+    keygen_with::<LIMBS>(serde_json::from_value(document)?)
+}
+
+fn keygen(options: &Options) -> Result<String> {
+    if let Some(name) = &options.group {
+        return match name.as_str() {
+            "modp2048" => keygen_with(MODP_2048),
+            "modp3072" => keygen_with(MODP_3072),
+            "modp4096" => keygen_with(MODP_4096),
+            "ffdhe2048" => keygen_with(FFDHE2048),
+            "ffdhe3072" => keygen_with(FFDHE3072),
+            "ffdhe4096" => keygen_with(FFDHE4096),
+            "ffdhe6144" => keygen_with(FFDHE6144),
+            "ffdhe8192" => keygen_with(FFDHE8192),
+            name => Err(Failure::Invalid(format!("unknown group {name}"))),
+        };
+    }
+
+    if let Some(bits) = options.bits {
+        // smallest width that holds the modulus
+        let width = WIDTHS
+            .into_iter()
+            .find(|width| (3..=*width).contains(&bits))
+            .ok_or_else(|| Failure::Invalid(format!("cannot generate a {bits}-bit modulus")))?;
+        return dispatch!(width, keygen_generated(bits));
+    }
+
+    let document: Value = serde_json::from_str(&read_input(options.input.as_deref())?)?;
+    dispatch!(width_of(&document)?, keygen_parsed(document))
+}
+
+fn prove_document<const LIMBS: usize>(
+    document: Value,
+    rounds: usize,
+    context: &[u8],
+) -> Result<String> {
+    let key: Key<LIMBS> = serde_json::from_value(document)?;
+    // the key file may have been edited since keygen, never prove in an unchecked group
+    let params = PublicParams::try_from(key.parameters)
+        .map_err(|error| Failure::Invalid(format!("invalid parameters: {error}")))?;
+
+    let (residue, proofs) =
+        prove_with_rounds(&params, &Witness::new(&params, key.secret), rounds, context);
+    if residue != key.residue {
+        return Err(Failure::Invalid(
+            "secret does not match residue".to_string(),
+        ));
+    }
+
+    let proof = ProofDocument {
+        parameters: key.parameters,
+        residue,
+        context: context.to_vec(),
+        proofs,
+    };
+
+    Ok(serde_json::to_string_pretty(&proof)?)
+}
+
+fn prove(options: &Options) -> Result<String> {
+    let document: Value = serde_json::from_str(&read_input(options.input.as_deref())?)?;
+    let context = options.context.as_deref().unwrap_or_default().as_bytes();
+
+    dispatch!(
+        width_of(&document)?,
+        prove_document(document, options.rounds(), context)
+    )
+}
+
+/// Check `proof` against the parameters and residue of `key` and the expected `context`
+fn verify_proof<const LIMBS: usize>(
+    proof: ProofDocument<LIMBS>,
+    key: &PublicKey<LIMBS>,
+    min_rounds: usize,
+    context: &[u8],
+) -> Result<String> {
+    if proof.parameters != key.parameters || proof.residue != key.residue {
+        return Err(Failure::WrongKey);
+    }
+
+    // the key may come from anyone as well, its parameters are checked in full
+    let params =
+        PublicParams::try_from(key.parameters).map_err(|error| Failure::Rejected(error.into()))?;
+
+    verify_with_min_rounds(&params, &key.residue, min_rounds, context, proof.proofs)
+        .map_err(Failure::Rejected)?;
+
+    Ok("valid".to_string())
+}
+
+fn verify_document<const LIMBS: usize>(
+    document: Value,
+    key: Value,
+    min_rounds: usize,
+    context: &[u8],
+) -> Result<String> {
+    let proof: ProofDocument<LIMBS> = serde_json::from_value(document)?;
+    let key: PublicKey<LIMBS> = serde_json::from_value(key)?;
+
+    verify_proof(proof, &key, min_rounds, context)
+}
+
+fn verify(options: &Options) -> Result<String> {
+    let key = options
+        .key
+        .as_deref()
+        .ok_or_else(|| Failure::Invalid("verify needs the --key to check against".to_string()))?;
+    let key: Value = serde_json::from_str(&read_input(Some(key))?)?;
+    let document: Value = serde_json::from_str(&read_input(options.input.as_deref())?)?;
+    let context = options.context.as_deref().unwrap_or_default().as_bytes();
+
+    dispatch!(
+        width_of(&document)?,
+        verify_document(document, key, options.rounds(), context)
+    )
+}
+
+fn public_document<const LIMBS: usize>(document: Value) -> Result<String> {
+    // a key has everything a public key has, the secret is dropped
+    let key: PublicKey<LIMBS> = serde_json::from_value(document)?;
+
+    Ok(serde_json::to_string_pretty(&key)?)
+}
+
+fn public(options: &Options) -> Result<String> {
+    let document: Value = serde_json::from_str(&read_input(options.input.as_deref())?)?;
+
+    dispatch!(width_of(&document)?, public_document(document))
+}
+
+fn inspect_document<const LIMBS: usize>(document: Value) -> Result<String> {
+    let parameters: ModpParameters<LIMBS> =
+        serde_json::from_value(document.get("parameters").unwrap_or(&document).clone())?;
+
+    let mut lines = vec![
+        format!("width: {} bits", Uint::<LIMBS>::BITS),
+        format!("modulus: {} bits", parameters.modulus.bits()),
+        format!("order: {} bits", parameters.order.bits()),
+        match parameters.validate() {
+            Ok(()) => "parameters: valid".to_string(),
+            Err(error) => format!("parameters: invalid, {error}"),
+        },
+    ];
+
+    if document.get("secret").is_some() {
+        let key: Key<LIMBS> = serde_json::from_value(document)?;
+        let matches = parameters
+            .group()
+            .map(|group| {
+                let params = PublicParams::new_unchecked(group);
+                Statement::from_witness(&params, &Witness::new(&params, key.secret)) == key.residue
+            })
+            .unwrap_or(false);
+
+        lines.insert(0, "kind: key".to_string());
+        lines.push(format!("secret matches residue: {}", yes_no(matches)));
+    } else if document.get("proofs").is_some() {
+        let proof: ProofDocument<LIMBS> = serde_json::from_value(document)?;
+
+        lines.insert(0, "kind: proof".to_string());
+        lines.push(format!("rounds: {}", proof.proofs.len()));
+        lines.push(format!("context: {}", hex::encode(&proof.context)));
+        // against the proof's own residue and context, this says nothing about who made it
+        let key = PublicKey {
+            parameters: proof.parameters,
+            residue: proof.residue,
+        };
+        let context = proof.context.clone();
+        lines.push(match verify_proof(proof, &key, ROUND_OF_VERIFY, &context) {
+            Ok(_) => "consistent: yes".to_string(),
+            Err(error) => format!("consistent: no, {error}"),
+        });
+    } else {
+        lines.insert(0, "kind: parameters".to_string());
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn inspect(options: &Options) -> Result<String> {
+    let document: Value = serde_json::from_str(&read_input(options.input.as_deref())?)?;
+
+    dispatch!(width_of(&document)?, inspect_document(document))
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn run(arguments: &[String]) -> Result<String> {
+    let (command, arguments) = arguments
+        .split_first()
+        .ok_or_else(|| Failure::Invalid(USAGE.to_string()))?;
+    let options = parse_options(arguments)?;
+
+    match command.as_str() {
+        "keygen" => keygen(&options),
+        "public" => public(&options),
+        "prove" => prove(&options),
+        "verify" => verify(&options),
+        "inspect" => inspect(&options),
+        _ => Err(Failure::Invalid(USAGE.to_string())),
+    }
+}
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+
+    match run(&arguments) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(failure) => {
+            eprintln!("zkp-dlog: {failure}");
+            match failure {
+                Failure::Rejected(_) | Failure::WrongKey => ExitCode::from(1),
+                Failure::Invalid(_) => ExitCode::from(2),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> Value {
+        serde_json::to_value(ModpParameters {
+            modulus: U256::from_u64(59),
+            order: U256::from_u64(29),
+            generator: U256::from_u64(4),
+        })
+        .unwrap()
+    }
+
+    fn key() -> Value {
+        let key = keygen_parsed::<{ U256::LIMBS }>(parameters()).unwrap();
+
+        serde_json::from_str(&key).unwrap()
+    }
+
+    fn proof(key: &Value, rounds: usize, context: &[u8]) -> Value {
+        let proof = prove_document::<{ U256::LIMBS }>(key.clone(), rounds, context);
+
+        serde_json::from_str(&proof.unwrap()).unwrap()
+    }
+
+    fn verify_with(
+        proof: &Value,
+        key: &Value,
+        min_rounds: usize,
+        context: &[u8],
+    ) -> Result<String> {
+        verify_document::<{ U256::LIMBS }>(proof.clone(), key.clone(), min_rounds, context)
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let arguments: Vec<String> = ["--context", "abc", "--key", "key.json", "proof.json"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let options = parse_options(&arguments).unwrap();
+
+        assert_eq!(options.context.as_deref(), Some("abc"));
+        assert_eq!(options.key.as_deref(), Some("key.json"));
+        assert_eq!(options.input.as_deref(), Some("proof.json"));
+        assert!(parse_options(&["--bits".to_string()]).is_err());
+        assert!(parse_options(&["--unknown".to_string()]).is_err());
+        assert!(parse_options(&["--soundness".to_string(), "0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_width() {
+        let key = key();
+
+        assert_eq!(width_of(&parameters()).unwrap(), 256);
+        assert_eq!(width_of(&proof(&key, 100, b"")).unwrap(), 256);
+        assert!(width_of(&Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_prove_verify() {
+        let key = key();
+        let proof = proof(&key, 100, b"session");
+
+        assert_eq!(proof["context"], "73657373696f6e");
+        assert_eq!(verify_with(&proof, &key, 100, b"session").unwrap(), "valid");
+        assert!(matches!(
+            verify_with(&proof, &key, 100, b"other"),
+            Err(Failure::Rejected(Error::RoundFailed { .. }))
+        ));
+
+        // the context of the document is not taken on trust
+        assert!(matches!(
+            verify_with(&proof, &key, 100, b""),
+            Err(Failure::Rejected(Error::RoundFailed { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_public_key() {
+        let key = key();
+        let public: Value =
+            serde_json::from_str(&public_document::<{ U256::LIMBS }>(key.clone()).unwrap())
+                .unwrap();
+
+        assert!(public.get("secret").is_none());
+        assert_eq!(public["residue"], key["residue"]);
+        assert_eq!(
+            verify_with(&proof(&key, 100, b""), &public, 100, b"").unwrap(),
+            "valid"
+        );
+    }
+
+    #[test]
+    fn test_reject_other_key() {
+        let expected = key();
+        // the toy group only has 29 residues, draw until they differ
+        let other = std::iter::repeat_with(key)
+            .find(|other| other["residue"] != expected["residue"])
+            .unwrap();
+        let proof = proof(&other, 100, b"");
+
+        // valid on its own, but not about the expected residue
+        assert_eq!(verify_with(&proof, &other, 100, b"").unwrap(), "valid");
+        assert!(matches!(
+            verify_with(&proof, &expected, 100, b""),
+            Err(Failure::WrongKey)
+        ));
+    }
+
+    #[test]
+    fn test_soundness() {
+        let key = key();
+        let proof = proof(&key, 40, b"");
+
+        assert_eq!(proof["proofs"].as_array().unwrap().len(), 40);
+        assert!(verify_with(&proof, &key, 40, b"").is_ok());
+        assert!(matches!(
+            verify_with(&proof, &key, 100, b""),
+            Err(Failure::Rejected(Error::TooFewRounds { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_reject_tampered() {
+        let mut key = key();
+        let mut proof = proof(&key, 100, b"");
+        proof["residue"] = proof["parameters"]["modulus"].clone();
+        key["residue"] = proof["residue"].clone();
+
+        assert!(matches!(
+            verify_with(&proof, &key, 100, b""),
+            Err(Failure::Rejected(Error::ResidueOutOfRange))
+        ));
+    }
+
+    #[test]
+    fn test_reject_bad_parameters() {
+        let mut key = key();
+        let mut proof = proof(&key, 100, b"");
+        proof["parameters"]["generator"] = proof["parameters"]["modulus"].clone();
+        key["parameters"] = proof["parameters"].clone();
+
+        assert!(matches!(
+            verify_with(&proof, &key, 100, b""),
+            Err(Failure::Rejected(Error::InvalidParameters(_)))
+        ));
+        assert!(matches!(
+            prove_document::<{ U256::LIMBS }>(key, 100, b""),
+            Err(Failure::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_is_redacted() {
+        let key: Key<{ U256::LIMBS }> = serde_json::from_value(key()).unwrap();
+        let printed = format!("{key:?}");
+
+        assert!(printed.contains("<redacted>"));
+        assert!(!printed.contains(&format!("{:?}", key.secret)));
+    }
+
+    #[test]
+    fn test_inspect() {
+        let output = inspect_document::<{ U256::LIMBS }>(proof(&key(), 100, b"")).unwrap();
+
+        assert!(output.starts_with("kind: proof\n"));
+        assert!(output.contains("rounds: 100"));
+        assert!(output.contains("consistent: yes"));
+    }
+}
@@ -0,0 +1,302 @@
+//! Chaum–Pedersen proof of equality of discrete logs (DLEQ).
+//!
+//! Proves that one secret x satisfies both `y1 = g1^x` and `y2 = g2^x`, e.g. that a VRF output
+//! or a decryption share was computed with the key behind a public key, or that
+//! (g1, y1, g2, y2) is a Diffie-Hellman tuple.
+//!
+//! It is the Schnorr proof run over both bases with one nonce and one challenge: the prover
+//! commits to `h1 = g1^r` and `h2 = g2^r` and answers `c` with `s = r + c * x (mod order)`.
+//! The verifier checks `g1^s = h1 * y1^c` and `g2^s = h2 * y2^c`.
+
+use crypto_bigint::rand_core::{CryptoRngCore, OsRng};
+
+use crate::{check_bases, new_transcript, Error, Group, PublicParams, Witness};
+
+// protocol label absorbed first into the transcript of the dleq proof
+const PROTOCOL_LABEL: &[u8] = b"dlog-chaum-pedersen";
+
+/// The public statement: y1 = g1^x and y2 = g2^x for the same x
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound = "G::Element: crate::encoding::FixedBytes")
+)]
+pub struct DleqStatement<G: Group> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub g1: G::Element,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub y1: G::Element,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub g2: G::Element,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub y2: G::Element,
+}
+
+impl<G: Group> DleqStatement<G> {
+    /// Statement of the residues of `witness` to the bases `g1` and `g2`
+    pub fn from_witness(
+        params: &PublicParams<G>,
+        g1: &G::Element,
+        g2: &G::Element,
+        witness: &Witness<G>,
+    ) -> Self {
+        let group = params.group();
+        let secret = witness.expose_secret();
+
+        Self {
+            g1: *g1,
+            y1: group.exp(g1, secret),
+            g2: *g2,
+            y2: group.exp(g2, secret),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        bound = "G::Element: crate::encoding::FixedBytes, G::Scalar: crate::encoding::FixedBytes"
+    )
+)]
+pub struct DleqProof<G: Group> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub h1: G::Element, // h1 = g1^r
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub h2: G::Element, // h2 = g2^r
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub s: G::Scalar, // s = (r + c * x) (mod order)
+}
+
+/// Prove that the same secret x gives y1 = g1^x and y2 = g2^x
+///
+/// Returns (statement, proof)
+pub fn prove_dleq<G: Group>(
+    params: &PublicParams<G>,
+    g1: &G::Element,
+    g2: &G::Element,
+    witness: &Witness<G>,
+) -> (DleqStatement<G>, DleqProof<G>) {
+    prove_dleq_with_context(params, g1, g2, witness, &[])
+}
+
+/// Same as [`prove_dleq`], with the proof bound to a caller chosen `context`
+pub fn prove_dleq_with_context<G: Group>(
+    params: &PublicParams<G>,
+    g1: &G::Element,
+    g2: &G::Element,
+    witness: &Witness<G>,
+    context: &[u8],
+) -> (DleqStatement<G>, DleqProof<G>) {
+    prove_dleq_with_rng(params, g1, g2, witness, context, &mut OsRng)
+}
+
+/// Same as [`prove_dleq_with_context`], drawing the nonce from `rng` instead of the OS
+pub fn prove_dleq_with_rng<G: Group>(
+    params: &PublicParams<G>,
+    g1: &G::Element,
+    g2: &G::Element,
+    witness: &Witness<G>,
+    context: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> (DleqStatement<G>, DleqProof<G>) {
+    let group = params.group();
+    let secret = witness.expose_secret();
+    let statement = DleqStatement::from_witness(params, g1, g2, witness);
+
+    // commit to h1 = g1^r and h2 = g2^r with the same r
+    let r = group.random_scalar(rng);
+    let h1 = group.exp(g1, &r);
+    let h2 = group.exp(g2, &r);
+
+    // s = r + c * x (mod order)
+    let c = get_challenge_by_hashing(group, &statement, context, &h1, &h2);
+    let s = group.scalar_add(&r, &group.scalar_mul(&c, secret));
+
+    (statement, DleqProof { h1, h2, s })
+}
+
+/// Evaluates to Ok if `proof` shows that y1 and y2 have the same discrete log to g1 and g2
+///
+/// Errors are reported as for a proof of a single round 0.
+pub fn verify_dleq<G: Group>(
+    params: &PublicParams<G>,
+    statement: &DleqStatement<G>,
+    proof: DleqProof<G>,
+) -> Result<(), Error> {
+    verify_dleq_with_context(params, statement, &[], proof)
+}
+
+/// Same as [`verify_dleq`], for proofs made by [`prove_dleq_with_context`]
+pub fn verify_dleq_with_context<G: Group>(
+    params: &PublicParams<G>,
+    statement: &DleqStatement<G>,
+    context: &[u8],
+    proof: DleqProof<G>,
+) -> Result<(), Error> {
+    let group = params.group();
+    let DleqStatement { g1, y1, g2, y2 } = statement;
+    let DleqProof { h1, h2, s } = proof;
+    check_bases(group, &[*g1, *g2])?;
+    if !group.contains(y1) || !group.contains(y2) {
+        return Err(Error::ResidueOutOfRange);
+    }
+    if !group.contains(&h1) || !group.contains(&h2) {
+        return Err(Error::NonCanonicalCommitment { round: 0 });
+    }
+    if !group.is_canonical_scalar(&s) {
+        return Err(Error::NonCanonicalResponse { round: 0 });
+    }
+
+    let c = get_challenge_by_hashing(group, statement, context, &h1, &h2);
+
+    // g1 ^ s = h1 * y1 ^ c and g2 ^ s = h2 * y2 ^ c
+    let first = group.exp(g1, &s) == group.operate(&h1, &group.exp(y1, &c));
+    let second = group.exp(g2, &s) == group.operate(&h2, &group.exp(y2, &c));
+
+    if !first || !second {
+        return Err(Error::RoundFailed { round: 0 });
+    }
+
+    Ok(())
+}
+
+/// Derive challenge `c` from a transcript over the whole statement and both commitments
+fn get_challenge_by_hashing<G: Group>(
+    group: &G,
+    statement: &DleqStatement<G>,
+    context: &[u8],
+    h1: &G::Element,
+    h2: &G::Element,
+) -> G::Scalar {
+    let mut transcript = new_transcript(PROTOCOL_LABEL, group, &statement.y1, context);
+    transcript.append_element(b"g1", group, &statement.g1);
+    transcript.append_element(b"g2", group, &statement.g2);
+    transcript.append_element(b"y2", group, &statement.y2);
+    transcript.append_element(b"h1", group, h1);
+    transcript.append_element(b"h2", group, h2);
+
+    group.challenge_scalar(&mut transcript, b"c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{large_params, params, prove_seeded},
+        Ristretto255,
+    };
+    use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn test_positive() {
+        let params = params(47, 23, 2);
+        let witness = Witness::new(&params, U256::from_u64(17));
+        let g1 = params.group().generator();
+        let g2 = params.group().exp_generator(&U256::from_u64(5));
+        let (statement, proof) = prove_dleq(&params, &g1, &g2, &witness);
+
+        assert_eq!(
+            statement.y1,
+            params.group().exp_generator(&U256::from_u64(17))
+        );
+        assert_eq!(verify_dleq(&params, &statement, proof), Ok(()));
+    }
+
+    #[test]
+    fn test_different_logs() {
+        let params = large_params();
+        let group = params.group();
+        let g1 = group.generator();
+        let g2 = group.exp_generator(&U256::from_u64(5));
+        let (mut statement, proof) = prove_dleq(
+            &params,
+            &g1,
+            &g2,
+            &Witness::new(&params, U256::from_u64(10)),
+        );
+
+        // y2 = g2^11 while y1 = g1^10
+        statement.y2 = group.exp(&g2, &U256::from_u64(11));
+        assert_eq!(
+            verify_dleq(&params, &statement, proof),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_degenerate_base() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let g1 = params.group().generator();
+        let (statement, proof) = prove_dleq(&params, &g1, &params.group().identity(), &witness);
+
+        assert_eq!(
+            verify_dleq(&params, &statement, proof),
+            Err(Error::BaseOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_tampered_response() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let g1 = params.group().generator();
+        let g2 = params.group().exp_generator(&U256::from_u64(3));
+        let (statement, mut proof) = prove_dleq(&params, &g1, &g2, &witness);
+        proof.s = params.group().scalar_add(&proof.s, &U256::ONE);
+
+        assert_eq!(
+            verify_dleq(&params, &statement, proof),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_ordered_bases() {
+        let params = PublicParams::new(Ristretto255::new()).unwrap();
+        let group = params.group();
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let witness = Witness::random(&params, &mut rng);
+        let (g1, g2) = (
+            group.generator(),
+            group.exp_generator(&group.random_scalar(&mut rng)),
+        );
+        let (statement, proof) =
+            prove_seeded(|rng| prove_dleq_with_rng(&params, &g1, &g2, &witness, b"alice", rng));
+        assert_eq!(
+            verify_dleq_with_context(&params, &statement, b"alice", proof),
+            Ok(())
+        );
+
+        // (g2, y2, g1, y1) is as true a statement, but c commits to which base is g1
+        let swapped = DleqStatement::from_witness(&params, &g2, &g1, &witness);
+        let DleqProof { h1, h2, s } = proof;
+        assert_eq!(
+            verify_dleq_with_context(&params, &swapped, b"alice", DleqProof { h1: h2, h2: h1, s }),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_same_base() {
+        // g1 = g2 is a Schnorr proof with a second copy of the commitment, still sound
+        let params = params(59, 29, 4);
+        let g = params.group().generator();
+        let (statement, proof) =
+            prove_dleq(&params, &g, &g, &Witness::new(&params, U256::from_u64(10)));
+        assert_eq!(verify_dleq(&params, &statement, proof), Ok(()));
+
+        let other = DleqStatement {
+            y2: params.group().exp_generator(&U256::from_u64(11)),
+            ..statement
+        };
+        assert_eq!(
+            verify_dleq(&params, &other, proof),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Canonical byte encoding, shared by the transcript and the wire format.
+//!
+//! Integers are big-endian and fixed-width (`LIMBS * 8` bytes), independent of the platform.
+//! Decoding is strict: wrong lengths and values outside [0, p) or [0, q) are rejected,
+//! so every proof has exactly one encoding.
+
+use crypto_bigint::{Limb, Uint};
+
+use crate::{Error, Group, Proof, Proofs, SchnorrProof, Statement};
+
+/// Fixed-width big-endian bytes of `value`
+pub fn uint_to_bytes<const LIMBS: usize>(value: &Uint<LIMBS>) -> Vec<u8> {
+    value
+        .as_words()
+        .iter()
+        .rev()
+        .flat_map(|word| word.to_be_bytes())
+        .collect()
+}
+
+/// Inverse of [`uint_to_bytes`], `None` unless `bytes` is exactly `LIMBS * 8` long
+pub fn uint_from_bytes<const LIMBS: usize>(bytes: &[u8]) -> Option<Uint<LIMBS>> {
+    if bytes.len() != LIMBS * Limb::BYTES {
+        return None;
+    }
+
+    Some(Uint::from_be_slice(bytes))
+}
+
+/// Fixed-width bytes of a value on its own, without the group it belongs to
+///
+/// Used where no group is at hand (e.g. serde), range checks are then left to the verifier.
+pub trait FixedBytes: Sized {
+    fn to_fixed_bytes(&self) -> Vec<u8>;
+
+    fn from_fixed_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl<const LIMBS: usize> FixedBytes for Uint<LIMBS> {
+    fn to_fixed_bytes(&self) -> Vec<u8> {
+        uint_to_bytes(self)
+    }
+
+    fn from_fixed_bytes(bytes: &[u8]) -> Option<Self> {
+        uint_from_bytes(bytes)
+    }
+}
+
+/// Wire format of statements and proofs, with canonical checks against the group
+pub trait Encoding<G: Group>: Sized {
+    fn to_bytes(&self, group: &G) -> Vec<u8>;
+
+    fn from_bytes(group: &G, bytes: &[u8]) -> Result<Self, Error>;
+}
+
+/// The residue y
+impl<G: Group> Encoding<G> for Statement<G> {
+    fn to_bytes(&self, group: &G) -> Vec<u8> {
+        group.encode_element(&self.residue())
+    }
+
+    fn from_bytes(group: &G, bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != group.element_len() {
+            return Err(Error::InvalidLength {
+                expected: group.element_len(),
+                found: bytes.len(),
+            });
+        }
+        let residue = group
+            .decode_element(bytes)
+            .ok_or(Error::ResidueOutOfRange)?;
+
+        Ok(Statement::new(residue))
+    }
+}
+
+/// `h || s`
+impl<G: Group> Encoding<G> for Proof<G> {
+    fn to_bytes(&self, group: &G) -> Vec<u8> {
+        encode_pair(group, &self.h, &self.s)
+    }
+
+    fn from_bytes(group: &G, bytes: &[u8]) -> Result<Self, Error> {
+        let (h, s) = decode_pair(group, bytes, 0)?;
+
+        Ok(Proof { h, s })
+    }
+}
+
+/// Concatenation of every round, the number of rounds follows from the length
+impl<G: Group> Encoding<G> for Proofs<G> {
+    fn to_bytes(&self, group: &G) -> Vec<u8> {
+        self.iter()
+            .flat_map(|proof| proof.to_bytes(group))
+            .collect()
+    }
+
+    fn from_bytes(group: &G, bytes: &[u8]) -> Result<Self, Error> {
+        let proof_len = group.element_len() + group.scalar_len();
+        if !bytes.len().is_multiple_of(proof_len) {
+            return Err(Error::PartialRound {
+                multiple: proof_len,
+                found: bytes.len(),
+            });
+        }
+
+        bytes
+            .chunks(proof_len)
+            .enumerate()
+            .map(|(round, chunk)| {
+                let (h, s) = decode_pair(group, chunk, round)?;
+
+                Ok(Proof { h, s })
+            })
+            .collect()
+    }
+}
+
+/// `h || s`
+impl<G: Group> Encoding<G> for SchnorrProof<G> {
+    fn to_bytes(&self, group: &G) -> Vec<u8> {
+        encode_pair(group, &self.h, &self.s)
+    }
+
+    fn from_bytes(group: &G, bytes: &[u8]) -> Result<Self, Error> {
+        let (h, s) = decode_pair(group, bytes, 0)?;
+
+        Ok(SchnorrProof { h, s })
+    }
+}
+
+fn encode_pair<G: Group>(group: &G, h: &G::Element, s: &G::Scalar) -> Vec<u8> {
+    let mut bytes = group.encode_element(h);
+    bytes.extend(group.encode_scalar(s));
+    bytes
+}
+
+fn decode_pair<G: Group>(
+    group: &G,
+    bytes: &[u8],
+    round: usize,
+) -> Result<(G::Element, G::Scalar), Error> {
+    let expected = group.element_len() + group.scalar_len();
+    if bytes.len() != expected {
+        return Err(Error::InvalidLength {
+            expected,
+            found: bytes.len(),
+        });
+    }
+
+    let (h_bytes, s_bytes) = bytes.split_at(group.element_len());
+    let h = group
+        .decode_element(h_bytes)
+        .ok_or(Error::NonCanonicalCommitment { round })?;
+    let s = group
+        .decode_scalar(s_bytes)
+        .ok_or(Error::NonCanonicalResponse { round })?;
+
+    Ok((h, s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prove, prove_schnorr, test_util::params, verify, verify_schnorr, Witness};
+    use crypto_bigint::{U128, U256};
+
+    #[test]
+    fn test_uint_big_endian() {
+        let value = U128::from_be_hex("000102030405060708090a0b0c0d0e0f");
+        let bytes = uint_to_bytes(&value);
+
+        assert_eq!(bytes, (0..16).collect::<Vec<u8>>());
+        assert_eq!(uint_from_bytes::<{ U128::LIMBS }>(&bytes), Some(value));
+        assert_eq!(uint_from_bytes::<{ U128::LIMBS }>(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn test_proofs_round_trip() {
+        let params = params(59, 29, 4);
+        let group = params.group();
+        let (statement, proofs) = prove(&params, &Witness::new(&params, U256::from_u64(10)));
+        let bytes = proofs.to_bytes(group);
+        assert_eq!(bytes.len(), 100 * 64);
+
+        let decoded = Proofs::from_bytes(group, &bytes).unwrap();
+        assert_eq!(decoded, proofs);
+        assert_eq!(verify(&params, &statement, decoded), Ok(()));
+    }
+
+    #[test]
+    fn test_statement_round_trip() {
+        let params = params(59, 29, 4);
+        let group = params.group();
+        let statement =
+            Statement::from_witness(&params, &Witness::new(&params, U256::from_u64(10)));
+        let bytes = statement.to_bytes(group);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(Statement::from_bytes(group, &bytes), Ok(statement));
+
+        // y becomes y + p
+        let y = uint_from_bytes::<{ U256::LIMBS }>(&bytes).unwrap();
+        let shifted = uint_to_bytes(&y.wrapping_add(&U256::from_u64(59)));
+        assert_eq!(
+            Statement::from_bytes(group, &shifted),
+            Err(Error::ResidueOutOfRange)
+        );
+        assert_eq!(
+            Statement::from_bytes(group, &bytes[1..]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                found: 31
+            })
+        );
+    }
+
+    #[test]
+    fn test_schnorr_round_trip() {
+        let params = params(59, 29, 4);
+        let group = params.group();
+        let (statement, proof) = prove_schnorr(&params, &Witness::new(&params, U256::from_u64(10)));
+        let bytes = proof.to_bytes(group);
+
+        let decoded = SchnorrProof::from_bytes(group, &bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(verify_schnorr(&params, &statement, decoded), Ok(()));
+    }
+
+    #[test]
+    fn test_reject_non_canonical() {
+        let params = params(59, 29, 4);
+        let group = params.group();
+        let (_, proofs) = prove(&params, &Witness::new(&params, U256::from_u64(10)));
+        let mut bytes = proofs.to_bytes(group);
+
+        // h of round 3 becomes h + p
+        let h = uint_from_bytes::<{ U256::LIMBS }>(&bytes[3 * 64..3 * 64 + 32]).unwrap();
+        bytes[3 * 64..3 * 64 + 32]
+            .copy_from_slice(&uint_to_bytes(&h.wrapping_add(&U256::from_u64(59))));
+        assert_eq!(
+            Proofs::from_bytes(group, &bytes),
+            Err(Error::NonCanonicalCommitment { round: 3 })
+        );
+
+        // s of round 0 becomes q
+        let mut bytes = proofs.to_bytes(group);
+        bytes[32..64].copy_from_slice(&uint_to_bytes(&U256::from_u64(29)));
+        assert_eq!(
+            Proofs::from_bytes(group, &bytes),
+            Err(Error::NonCanonicalResponse { round: 0 })
+        );
+
+        assert_eq!(
+            Proofs::from_bytes(group, &bytes[..6399]),
+            Err(Error::PartialRound {
+                multiple: 64,
+                found: 6399
+            })
+        );
+        assert_eq!(
+            Proof::from_bytes(group, &bytes[..63]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                found: 63
+            })
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Errors reported by the crate.
+
+use std::fmt;
+
+/// Why a set of group parameters (p, q, g) was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterError {
+    /// p failed the primality test, or is even
+    CompositeModulus,
+    /// q failed the primality test
+    CompositeOrder,
+    /// q does not divide p - 1, so there is no subgroup of order q
+    OrderNotDividingGroup,
+    /// g is not in [0, p)
+    GeneratorOutOfRange,
+    /// g is 0, 1 or p - 1, which generate nothing useful
+    DegenerateGenerator,
+    /// g^q != 1 (mod p), so g does not have order q
+    WrongGeneratorOrder,
+    /// p != 3 (mod 4), square roots mod p are only taken for such moduli
+    UnsupportedModulus,
+    /// 4a^3 + 27b^2 = 0 (mod p), the curve has a singular point
+    SingularCurve,
+    /// The generator G does not satisfy the curve equation
+    GeneratorNotOnCurve,
+    /// n is too small to be the number of points on the curve, G does not generate all of them
+    CofactorNotOne,
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::CompositeModulus => "modulus p is not an odd prime",
+            Self::CompositeOrder => "subgroup order q is not prime",
+            Self::OrderNotDividingGroup => "subgroup order q does not divide p - 1",
+            Self::GeneratorOutOfRange => "generator g is not reduced mod p",
+            Self::DegenerateGenerator => "generator g is 0, 1 or p - 1",
+            Self::WrongGeneratorOrder => "generator g does not have order q",
+            Self::UnsupportedModulus => "modulus p is not 3 mod 4",
+            Self::SingularCurve => "curve is singular",
+            Self::GeneratorNotOnCurve => "generator G is not on the curve",
+            Self::CofactorNotOne => "curve does not have prime order n",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+/// Why a proof was rejected, or could not be made
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The group parameters themselves are unusable
+    InvalidParameters(ParameterError),
+    /// The proof has fewer rounds than the verifier requires
+    TooFewRounds { minimum: usize, found: usize },
+    /// The residue y is not a canonical element of the group
+    ResidueOutOfRange,
+    /// A base of the statement is the identity or not a canonical element of the group
+    BaseOutOfRange,
+    /// The commitment h of this round is not a canonical element of the group
+    NonCanonicalCommitment { round: usize },
+    /// The response s of this round, or its challenge share c in an OR-proof, is not reduced
+    NonCanonicalResponse { round: usize },
+    /// g^s != h * y^b in this round, the first one failing
+    RoundFailed { round: usize },
+    /// An encoded proof does not have the expected number of bytes
+    InvalidLength { expected: usize, found: usize },
+    /// Encoded rounds are not a whole number of `multiple` bytes each
+    PartialRound { multiple: usize, found: usize },
+    /// An interactive transcript does not have one round per challenge
+    WrongRoundCount { expected: usize, found: usize },
+    /// An OR- / AND-proof does not have one branch (and base) per statement
+    WrongBranchCount { expected: usize, found: usize },
+    /// The challenges of the branches of an OR-proof do not add up to the hashed challenge
+    ChallengeMismatch,
+    /// The prover's witness is not the discrete log of the statement it was given for
+    WrongWitness,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidParameters(error) => write!(f, "invalid parameters: {error}"),
+            Self::TooFewRounds { minimum, found } => {
+                write!(f, "expected at least {minimum} rounds, found {found}")
+            }
+            Self::ResidueOutOfRange => f.write_str("residue y is not in the group"),
+            Self::BaseOutOfRange => f.write_str("base is the identity or not in the group"),
+            Self::NonCanonicalCommitment { round } => {
+                write!(f, "commitment of round {round} is not in the group")
+            }
+            Self::NonCanonicalResponse { round } => {
+                write!(f, "response of round {round} is not reduced")
+            }
+            Self::RoundFailed { round } => write!(f, "round {round} does not verify"),
+            Self::InvalidLength { expected, found } => {
+                write!(f, "expected {expected} bytes, found {found}")
+            }
+            Self::PartialRound { multiple, found } => {
+                write!(f, "expected a multiple of {multiple} bytes, found {found}")
+            }
+            Self::WrongRoundCount { expected, found } => {
+                write!(f, "expected {expected} rounds, found {found}")
+            }
+            Self::WrongBranchCount { expected, found } => {
+                write!(f, "expected {expected} branches, found {found}")
+            }
+            Self::ChallengeMismatch => f.write_str("branch challenges do not add up"),
+            Self::WrongWitness => f.write_str("witness does not match the statement"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidParameters(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParameterError> for Error {
+    fn from(error: ParameterError) -> Self {
+        Self::InvalidParameters(error)
+    }
+}
+
+/// Why no witness could be extracted from two transcripts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtractionError {
+    /// The transcripts do not share their commitment h
+    CommitmentMismatch,
+    /// Both transcripts answer the same challenge, they carry no more than one of them
+    SameChallenge,
+    /// The transcript at this index (0 or 1) does not verify
+    NotAccepting { transcript: usize },
+}
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CommitmentMismatch => f.write_str("transcripts have different commitments"),
+            Self::SameChallenge => f.write_str("transcripts answer the same challenge"),
+            Self::NotAccepting { transcript } => {
+                write!(f, "transcript {transcript} does not verify")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtractionError {}
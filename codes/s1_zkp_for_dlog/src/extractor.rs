@@ -0,0 +1,224 @@
+//! Special soundness, made concrete.
+//!
+//! Two accepting transcripts `(h, c1, s1)` and `(h, c2, s2)` with the same commitment and
+//! different challenges give `g^(s1 - s2) = y^(c1 - c2)`, so `x = (s1 - s2) / (c1 - c2) (mod order)`.
+//! For the binary rounds of [`prove`](crate::prove) the challenges are 0 and 1 and `x = s1 - s0`.
+//!
+//! Anyone able to answer two challenges for one commitment therefore knows x, which is why
+//! a prover must never reuse a nonce `r`.
+
+use crate::{ExtractionError, Group, Proof, PublicParams, Statement, Witness};
+
+/// One accepted exchange of a sigma protocol: commitment, challenge and response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Conversation<G: Group> {
+    pub h: G::Element,        // h = g^r
+    pub challenge: G::Scalar, // c
+    pub s: G::Scalar,         // s = r + c * x (mod order)
+}
+
+impl<G: Group> Conversation<G> {
+    /// A round of the binary protocol that answered challenge bit `bit`
+    pub fn from_round(params: &PublicParams<G>, proof: &Proof<G>, bit: bool) -> Self {
+        Self {
+            h: proof.h,
+            challenge: params.group().scalar_from_u64(bit.into()),
+            s: proof.s,
+        }
+    }
+
+    /// Whether `g^s = h * y^c`
+    pub fn accepts(&self, params: &PublicParams<G>, statement: &Statement<G>) -> bool {
+        let group = params.group();
+        let lhs = group.exp_generator(&self.s);
+        let rhs = group.operate(&self.h, &group.exp(&statement.residue(), &self.challenge));
+
+        lhs == rhs
+    }
+}
+
+/// Recover x with y = g^x from two accepting conversations sharing their commitment
+pub fn extract_witness<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    first: &Conversation<G>,
+    second: &Conversation<G>,
+) -> Result<Witness<G>, ExtractionError> {
+    if first.h != second.h {
+        return Err(ExtractionError::CommitmentMismatch);
+    }
+    for (transcript, conversation) in [first, second].into_iter().enumerate() {
+        if !conversation.accepts(params, statement) {
+            return Err(ExtractionError::NotAccepting { transcript });
+        }
+    }
+
+    // c1 - c2 is invertible as long as it is not 0, the order is prime
+    let group = params.group();
+    let challenge_difference = group.scalar_sub(&first.challenge, &second.challenge);
+    let inverse = group
+        .scalar_invert(&challenge_difference)
+        .ok_or(ExtractionError::SameChallenge)?;
+    let response_difference = group.scalar_sub(&first.s, &second.s);
+
+    Ok(Witness::new(
+        params,
+        group.scalar_mul(&response_difference, &inverse),
+    ))
+}
+
+/// A prover run as a black box: it commits, then answers one challenge
+///
+/// Cloning it after the commitment rewinds it to that point, with the same nonce inside.
+pub trait RewindableProver<G: Group>: Clone {
+    /// Pick a nonce r and return h = g^r
+    fn commit(&mut self) -> G::Element;
+
+    /// Answer the challenge for the committed nonce
+    fn respond(self, challenge: &G::Scalar) -> G::Scalar;
+}
+
+/// Run `prover` once up to its commitment, then answer both `challenges` from that same state
+///
+/// Returns the witness of any prover that convinces the verifier on both, honest or not.
+pub fn rewind<G: Group, P: RewindableProver<G>>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    mut prover: P,
+    challenges: [G::Scalar; 2],
+) -> Result<Witness<G>, ExtractionError> {
+    let h = prover.commit();
+    let rewound = prover.clone();
+
+    let [first, second] = challenges;
+    let first = Conversation {
+        h,
+        challenge: first,
+        s: prover.respond(&first),
+    };
+    let second = Conversation {
+        h,
+        challenge: second,
+        s: rewound.respond(&second),
+    };
+
+    extract_witness(params, statement, &first, &second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::large_params;
+    use crate::{prove, ModpGroup};
+    use crypto_bigint::{rand_core::OsRng, U256};
+
+    #[derive(Clone)]
+    struct HonestProver<'a> {
+        group: &'a ModpGroup<{ U256::LIMBS }>,
+        secret: U256,
+        r: U256,
+    }
+
+    impl RewindableProver<ModpGroup<{ U256::LIMBS }>> for HonestProver<'_> {
+        fn commit(&mut self) -> U256 {
+            self.r = self.group.random_scalar(&mut OsRng);
+            self.group.exp_generator(&self.r)
+        }
+
+        fn respond(self, challenge: &U256) -> U256 {
+            let cx = self.group.scalar_mul(challenge, &self.secret);
+            self.group.scalar_add(&self.r, &cx)
+        }
+    }
+
+    #[test]
+    fn test_extract_binary_rounds() {
+        let params = large_params();
+        let group = params.group();
+        let secret = U256::from_u64(123456789);
+        let witness = Witness::new(&params, secret);
+        let (statement, proofs) = prove(&params, &witness);
+
+        // same nonce, both bits: what a prover reusing r would give away
+        let r = group.random_scalar(&mut OsRng);
+        let h = group.exp_generator(&r);
+        let zero = Conversation::from_round(&params, &Proof { h, s: r }, false);
+        let one = Conversation::from_round(
+            &params,
+            &Proof {
+                h,
+                s: group.scalar_add(&r, &secret),
+            },
+            true,
+        );
+
+        assert_eq!(
+            extract_witness(&params, &statement, &one, &zero),
+            Ok(witness.clone())
+        );
+        assert_eq!(
+            extract_witness(&params, &statement, &zero, &one),
+            Ok(witness)
+        );
+
+        // two different rounds share no commitment
+        let other = Conversation::from_round(&params, &proofs[0], false);
+        assert_eq!(
+            extract_witness(&params, &statement, &one, &other),
+            Err(ExtractionError::CommitmentMismatch)
+        );
+    }
+
+    #[test]
+    fn test_rewind() {
+        let params = large_params();
+        let group = params.group();
+        let witness = Witness::random(&params, &mut OsRng);
+        let statement = Statement::from_witness(&params, &witness);
+        let prover = HonestProver {
+            group,
+            secret: *witness.expose_secret(),
+            r: U256::ZERO,
+        };
+
+        let challenges = [
+            group.random_scalar(&mut OsRng),
+            group.random_scalar(&mut OsRng),
+        ];
+        assert_eq!(
+            rewind(&params, &statement, prover.clone(), challenges),
+            Ok(witness.clone())
+        );
+
+        let bits = [group.scalar_from_u64(0), group.scalar_from_u64(1)];
+        assert_eq!(
+            rewind(&params, &statement, prover.clone(), bits),
+            Ok(witness)
+        );
+
+        let same = [challenges[0], challenges[0]];
+        assert_eq!(
+            rewind(&params, &statement, prover, same),
+            Err(ExtractionError::SameChallenge)
+        );
+    }
+
+    #[test]
+    fn test_reject_non_accepting() {
+        let params = large_params();
+        let group = params.group();
+        let witness = Witness::random(&params, &mut OsRng);
+        let statement = Statement::from_witness(&params, &witness);
+        let prover = HonestProver {
+            group,
+            secret: group.scalar_add(witness.expose_secret(), &U256::ONE), // does not know x
+            r: U256::ZERO,
+        };
+
+        let bits = [group.scalar_from_u64(1), group.scalar_from_u64(0)];
+        assert_eq!(
+            rewind(&params, &statement, prover, bits),
+            Err(ExtractionError::NotAccepting { transcript: 0 })
+        );
+    }
+}
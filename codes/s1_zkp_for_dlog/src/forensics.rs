@@ -0,0 +1,275 @@
+//! Incident response for nonce reuse.
+//!
+//! A round answering bit 0 reveals its nonce `r = s`, so any other round with the same
+//! commitment `h = g^r` answering bit 1 reveals its secret `x = s - r`, whatever statement
+//! it belongs to. [`scan`] looks for such repeated commitments across a collection of proofs
+//! and recovers every witness they leak, using [`extract_witness`].
+
+use std::collections::HashMap;
+
+use crate::{
+    extractor::{extract_witness, Conversation},
+    get_bits_by_hashing, Group, Proof, Proofs, PublicParams, Statement, Witness,
+};
+
+/// A proof as seen on the wire: statement y = g^x, context and rounds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Observation<G: Group> {
+    pub statement: Statement<G>,
+    pub context: Vec<u8>,
+    pub proofs: Proofs<G>,
+}
+
+/// Round `round` of the observation at index `proof`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub proof: usize,
+    pub round: usize,
+}
+
+/// A commitment h found in more than one round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReusedCommitment<G: Group> {
+    pub h: G::Element,
+    pub locations: Vec<Location>,
+}
+
+/// A statement whose witness leaked, with the two rounds it was recovered from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Compromised<G: Group> {
+    pub statement: Statement<G>,
+    pub witness: Witness<G>,
+    pub leaked_by: [Location; 2],
+}
+
+/// Everything [`scan`] found, in order of first appearance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report<G: Group> {
+    pub reused: Vec<ReusedCommitment<G>>,
+    pub compromised: Vec<Compromised<G>>,
+}
+
+/// Find repeated commitments in `observations` and the witnesses they leak
+///
+/// Rounds that do not verify are reported as reused but never used to recover a witness.
+pub fn scan<G: Group>(params: &PublicParams<G>, observations: &[Observation<G>]) -> Report<G> {
+    let group = params.group();
+    // challenge bit of every round, as the verifier derived it
+    let bits: Vec<_> = observations
+        .iter()
+        .map(|observation| {
+            let commitments: Vec<_> = observation.proofs.iter().map(|proof| proof.h).collect();
+            get_bits_by_hashing(
+                group,
+                &observation.statement.residue(),
+                &observation.context,
+                &commitments,
+            )
+        })
+        .collect();
+
+    // elements have no Hash, their canonical encoding does
+    let mut order = Vec::new();
+    let mut locations: HashMap<Vec<u8>, Vec<Location>> = HashMap::new();
+    for (proof, observation) in observations.iter().enumerate() {
+        for (round, Proof { h, .. }) in observation.proofs.iter().enumerate() {
+            let key = group.encode_element(h);
+            let entry = locations.entry(key.clone()).or_default();
+            if entry.is_empty() {
+                order.push(key);
+            }
+            entry.push(Location { proof, round });
+        }
+    }
+
+    let mut report = Report {
+        reused: Vec::new(),
+        compromised: Vec::new(),
+    };
+
+    for key in order {
+        let locations = &locations[&key];
+        if locations.len() < 2 {
+            continue;
+        }
+
+        let conversation = |location: &Location| {
+            let observation = &observations[location.proof];
+            let proof = &observation.proofs[location.round];
+            let bit = bits[location.proof][location.round];
+
+            (
+                observation.statement,
+                Conversation::from_round(params, proof, bit),
+                bit,
+            )
+        };
+
+        // a verifying bit 0 round gives away r, every verifying bit 1 round then gives away x
+        let nonce = locations.iter().find_map(|location| {
+            let (statement, zero, bit) = conversation(location);
+            (!bit && zero.accepts(params, &statement)).then_some((*location, zero))
+        });
+        if let Some((zero_location, zero)) = nonce {
+            for location in locations {
+                let (statement, one, bit) = conversation(location);
+                let known = report
+                    .compromised
+                    .iter()
+                    .any(|compromised| compromised.statement == statement);
+                if !bit || known {
+                    continue;
+                }
+
+                if let Ok(witness) = extract_witness(params, &statement, &one, &zero) {
+                    report.compromised.push(Compromised {
+                        statement,
+                        witness,
+                        leaked_by: [zero_location, *location],
+                    });
+                }
+            }
+        }
+
+        report.reused.push(ReusedCommitment {
+            h: observations[locations[0].proof].proofs[locations[0].round].h,
+            locations: locations.clone(),
+        });
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::large_params;
+    use crate::{prove_deterministic, prove_with_context, ModpGroup, ROUND_OF_VERIFY};
+    use crypto_bigint::{rand_core::OsRng, U256};
+
+    // a broken prover, drawing its nonces from a stuck RNG
+    fn prove_with_nonces(
+        params: &PublicParams<ModpGroup<{ U256::LIMBS }>>,
+        witness: &Witness<ModpGroup<{ U256::LIMBS }>>,
+        nonces: &[U256],
+        context: &[u8],
+    ) -> Observation<ModpGroup<{ U256::LIMBS }>> {
+        let (residue, proofs) = crate::prove_with_nonces(
+            params.group(),
+            witness.expose_secret(),
+            nonces.to_vec(),
+            context,
+        );
+
+        Observation {
+            statement: Statement::new(residue),
+            context: context.to_vec(),
+            proofs,
+        }
+    }
+
+    #[test]
+    fn test_honest_proofs() {
+        let params = large_params();
+        let witness = Witness::new(&params, U256::from_u64(42));
+        let observations: Vec<_> = (0..3)
+            .map(|i| {
+                let (statement, proofs) = prove_with_context(&params, &witness, &[i]);
+                Observation {
+                    statement,
+                    context: vec![i],
+                    proofs,
+                }
+            })
+            .collect();
+
+        let report = scan(&params, &observations);
+        assert!(report.reused.is_empty());
+        assert!(report.compromised.is_empty());
+    }
+
+    #[test]
+    fn test_deterministic_proofs() {
+        let params = large_params();
+        let witness = Witness::random(&params, &mut OsRng);
+        let observations: Vec<_> = [&b"first"[..], b"second", b"first"]
+            .into_iter()
+            .map(|context| {
+                let (statement, proofs) = prove_deterministic(&params, &witness, context);
+                Observation {
+                    statement,
+                    context: context.to_vec(),
+                    proofs,
+                }
+            })
+            .collect();
+
+        // a repeated proof repeats its commitments, with the same bits, leaking nothing
+        let report = scan(&params, &observations);
+        assert_eq!(report.reused.len(), ROUND_OF_VERIFY);
+        assert!(report
+            .reused
+            .iter()
+            .all(|reused| reused.locations[0].proof == 0 && reused.locations[1].proof == 2));
+        assert!(report.compromised.is_empty());
+    }
+
+    #[test]
+    fn test_same_statement() {
+        let params = large_params();
+        let witness = Witness::random(&params, &mut OsRng);
+        let nonces: Vec<_> = (0..ROUND_OF_VERIFY)
+            .map(|_| params.group().random_scalar(&mut OsRng))
+            .collect();
+        let observations = [
+            prove_with_nonces(&params, &witness, &nonces, b"first"),
+            prove_with_nonces(&params, &witness, &nonces, b"second"),
+        ];
+
+        let report = scan(&params, &observations);
+        assert_eq!(report.reused.len(), ROUND_OF_VERIFY);
+        assert_eq!(
+            report.reused[7].locations,
+            [
+                Location { proof: 0, round: 7 },
+                Location { proof: 1, round: 7 }
+            ]
+        );
+        assert_eq!(report.compromised.len(), 1);
+        assert_eq!(report.compromised[0].statement, observations[0].statement);
+        assert_eq!(report.compromised[0].witness, witness);
+    }
+
+    #[test]
+    fn test_across_statements() {
+        let params = large_params();
+        let witnesses = [
+            Witness::random(&params, &mut OsRng),
+            Witness::random(&params, &mut OsRng),
+        ];
+        let nonce = [params.group().random_scalar(&mut OsRng)];
+        let bit = |observation: &Observation<_>| {
+            let h = observation.proofs[0].h;
+            let residue = observation.statement.residue();
+            get_bits_by_hashing(params.group(), &residue, &observation.context, &[h])[0]
+        };
+
+        // one nonce shared by two different keys is enough, once the two bits differ
+        let first = prove_with_nonces(&params, &witnesses[0], &nonce, b"");
+        let second = (0u8..)
+            .map(|i| prove_with_nonces(&params, &witnesses[1], &nonce, &[i]))
+            .find(|second| bit(second) != bit(&first))
+            .unwrap();
+        let leaked = usize::from(bit(&second));
+        let observations = [first, second];
+
+        let report = scan(&params, &observations);
+        assert_eq!(report.reused.len(), 1);
+        assert_eq!(report.compromised.len(), 1);
+        assert_eq!(report.compromised[0].witness, witnesses[leaked]);
+        assert_eq!(
+            report.compromised[0].statement,
+            observations[leaked].statement
+        );
+    }
+}
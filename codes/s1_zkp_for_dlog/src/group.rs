@@ -0,0 +1,120 @@
+//! Groups the proofs work over.
+//!
+//! The protocols only need a cyclic group with a generator, its operation, exponentiation,
+//! an encoding of elements for the transcript, and arithmetic on exponents modulo the group order.
+//! [`ModpGroup`] is the prime order subgroup of the integers mod p the crate started with,
+//! [`WeierstrassCurve`] a prime order elliptic curve such as secp256k1 or P-256,
+//! and [`Ristretto255`] the prime order group on top of Curve25519.
+
+use std::fmt::Debug;
+
+use crypto_bigint::{
+    rand_core::{CryptoRngCore, OsRng},
+    subtle::ConditionallySelectable,
+};
+use zeroize::Zeroize;
+
+use crate::{ParameterError, Transcript};
+
+mod modp;
+mod ristretto;
+pub mod standard;
+mod weierstrass;
+
+pub use modp::{validate_parameters, validate_parameters_with_rng, ModpGroup, ModpParameters};
+pub use ristretto::{Ristretto255, RistrettoPoint};
+pub use weierstrass::{AffinePoint, CurveParameters, ProjectivePoint, WeierstrassCurve};
+
+pub trait Group {
+    /// Group element, e.g. a residue mod p
+    type Element: Copy + Eq + Debug + ConditionallySelectable;
+    /// Exponent, an integer modulo the order of the group, zeroized when it holds a secret
+    type Scalar: Copy + Eq + Debug + Zeroize + ConditionallySelectable;
+
+    /// Check the parameters of the group, e.g. primality of the modulus and order of g
+    ///
+    /// Expensive, meant to run once per group rather than once per proof.
+    fn validate(&self) -> Result<(), ParameterError> {
+        self.validate_with_rng(&mut OsRng)
+    }
+
+    /// Same as [`Self::validate`], drawing the Miller-Rabin bases from `rng` instead of the OS
+    fn validate_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(), ParameterError>;
+
+    /// The generator g
+    fn generator(&self) -> Self::Element;
+
+    /// The neutral element
+    fn identity(&self) -> Self::Element;
+
+    /// lhs * rhs
+    fn operate(&self, lhs: &Self::Element, rhs: &Self::Element) -> Self::Element;
+
+    /// element ^ -1
+    fn invert(&self, element: &Self::Element) -> Self::Element;
+
+    /// base ^ exp
+    fn exp(&self, base: &Self::Element, exp: &Self::Scalar) -> Self::Element;
+
+    /// g ^ exp
+    fn exp_generator(&self, exp: &Self::Scalar) -> Self::Element {
+        self.exp(&self.generator(), exp)
+    }
+
+    /// Whether `element` is a canonical member of the group generated by g
+    ///
+    /// Verifiers check every received element with this before using it.
+    fn contains(&self, element: &Self::Element) -> bool;
+
+    /// Whether `scalar` is reduced modulo the order of the group
+    ///
+    /// Verifiers check every received response with this, `s` and `s + order` pass the same equation.
+    fn is_canonical_scalar(&self, scalar: &Self::Scalar) -> bool;
+
+    /// scalar (mod order), for exponents such as a secret that may not be reduced yet
+    fn reduce_scalar(&self, scalar: &Self::Scalar) -> Self::Scalar;
+
+    /// Bytes identifying the group (modulus, curve, ...), absorbed into every transcript
+    fn encode_parameters(&self) -> Vec<u8>;
+
+    /// Length of [`Self::encode_element`]
+    fn element_len(&self) -> usize;
+
+    /// Canonical fixed-width bytes of an element, absorbed into transcripts and sent over the wire
+    fn encode_element(&self, element: &Self::Element) -> Vec<u8>;
+
+    /// Inverse of [`Self::encode_element`], `None` for anything but a canonical encoding
+    ///
+    /// Membership of the decoded element is still checked by [`Self::contains`].
+    fn decode_element(&self, bytes: &[u8]) -> Option<Self::Element>;
+
+    /// Length of [`Self::encode_scalar`]
+    fn scalar_len(&self) -> usize;
+
+    /// Canonical fixed-width bytes of an exponent
+    fn encode_scalar(&self, scalar: &Self::Scalar) -> Vec<u8>;
+
+    /// Inverse of [`Self::encode_scalar`], `None` for anything but a canonical encoding
+    fn decode_scalar(&self, bytes: &[u8]) -> Option<Self::Scalar>;
+
+    /// Uniformly random exponent
+    fn random_scalar(&self, rng: &mut impl CryptoRngCore) -> Self::Scalar;
+
+    /// lhs + rhs (mod order)
+    fn scalar_add(&self, lhs: &Self::Scalar, rhs: &Self::Scalar) -> Self::Scalar;
+
+    /// lhs * rhs (mod order)
+    fn scalar_mul(&self, lhs: &Self::Scalar, rhs: &Self::Scalar) -> Self::Scalar;
+
+    /// lhs - rhs (mod order)
+    fn scalar_sub(&self, lhs: &Self::Scalar, rhs: &Self::Scalar) -> Self::Scalar;
+
+    /// scalar ^ -1 (mod order), `None` for zero
+    fn scalar_invert(&self, scalar: &Self::Scalar) -> Option<Self::Scalar>;
+
+    /// A small integer as an exponent, e.g. a challenge bit
+    fn scalar_from_u64(&self, value: u64) -> Self::Scalar;
+
+    /// Full-width challenge exponent squeezed from `transcript`
+    fn challenge_scalar(&self, transcript: &mut Transcript, label: &[u8]) -> Self::Scalar;
+}
@@ -0,0 +1,484 @@
+//! Schnorr group: the subgroup of prime order q of the integers mod p.
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::{CryptoRngCore, OsRng},
+    Limb, NonZero, RandomMod, Uint,
+};
+
+use super::Group;
+use crate::{
+    encoding::{uint_from_bytes, uint_to_bytes},
+    mul_mod_wide,
+    primality::{is_probable_prime, MILLER_RABIN_ROUNDS},
+    ParameterError, Transcript,
+};
+
+/// Raw parameters (p, q, g) of a [`ModpGroup`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModpParameters<const LIMBS: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub modulus: Uint<LIMBS>, // p
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub order: Uint<LIMBS>, // q
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub generator: Uint<LIMBS>, // g
+}
+
+impl<const LIMBS: usize> ModpParameters<LIMBS> {
+    /// Precompute the group of these parameters, see [`ModpGroup::new`] for what is checked
+    pub fn group(&self) -> Result<ModpGroup<LIMBS>, ParameterError> {
+        ModpGroup::new(self.modulus, self.order, self.generator)
+    }
+
+    /// See [`validate_parameters`]
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        validate_parameters(&self.modulus, &self.order, &self.generator)
+    }
+
+    /// See [`validate_parameters_with_rng`]
+    pub fn validate_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(), ParameterError> {
+        validate_parameters_with_rng(&self.modulus, &self.order, &self.generator, rng)
+    }
+
+    /// `p || q || g`, each fixed-width big-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.modulus, self.order, self.generator]
+            .iter()
+            .flat_map(uint_to_bytes)
+            .collect()
+    }
+
+    /// Inverse of [`Self::to_bytes`], `None` on a wrong length
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 3 * LIMBS * Limb::BYTES {
+            return None;
+        }
+
+        let mut chunks = bytes.chunks(LIMBS * Limb::BYTES).map(uint_from_bytes);
+        Some(Self {
+            modulus: chunks.next()??,
+            order: chunks.next()??,
+            generator: chunks.next()??,
+        })
+    }
+}
+
+/// Integers mod p under multiplication, restricted to the subgroup of prime order q generated by g
+///
+/// Exponents and responses live mod q. Working in the order-q subgroup rather than all of
+/// (Z/pZ)* keeps every exponent uniformly distributed, nothing leaks through small subgroups.
+///
+/// With the `serde` feature it serializes as its [`ModpParameters`], deserializing runs the
+/// checks of [`ModpGroup::new`] but not the full [`validate_parameters`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "ModpParameters<LIMBS>", try_from = "ModpParameters<LIMBS>")
+)]
+pub struct ModpGroup<const LIMBS: usize> {
+    modulus: Uint<LIMBS>,
+    order: NonZero<Uint<LIMBS>>,
+    generator: Uint<LIMBS>,
+    rem_cac_cache: RemCaculateCache<LIMBS>,
+}
+
+impl<const LIMBS: usize> ModpGroup<LIMBS> {
+    /// Group of parameters (p, q, g), after checking them with [`validate_parameters`]
+    pub fn new_checked(
+        modulus: Uint<LIMBS>,
+        order: Uint<LIMBS>,
+        generator: Uint<LIMBS>,
+    ) -> Result<Self, ParameterError> {
+        Self::new_checked_with_rng(modulus, order, generator, &mut OsRng)
+    }
+
+    /// Same as [`Self::new_checked`], drawing the Miller-Rabin bases from `rng`
+    pub fn new_checked_with_rng(
+        modulus: Uint<LIMBS>,
+        order: Uint<LIMBS>,
+        generator: Uint<LIMBS>,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<Self, ParameterError> {
+        validate_parameters_with_rng(&modulus, &order, &generator, rng)?;
+
+        Self::new(modulus, order, generator)
+    }
+
+    /// Group of parameters (p, q, g), where g is expected to have prime order q mod p
+    ///
+    /// Only what the arithmetic relies on is checked (p odd and > 1, q > 0),
+    /// use [`Self::new_checked`] for untrusted parameters.
+    pub fn new(
+        modulus: Uint<LIMBS>,
+        order: Uint<LIMBS>,
+        generator: Uint<LIMBS>,
+    ) -> Result<Self, ParameterError> {
+        // montgomery reduction needs an odd modulus
+        if modulus <= <Uint<LIMBS>>::ONE || !modulus.bit_vartime(0) {
+            return Err(ParameterError::CompositeModulus);
+        }
+        let order = Option::from(NonZero::new(order)).ok_or(ParameterError::CompositeOrder)?;
+
+        Ok(Self {
+            modulus,
+            order,
+            generator,
+            rem_cac_cache: RemCaculateCache::new(&modulus),
+        })
+    }
+
+    /// The parameters (p, q, g) of this group
+    pub fn parameters(&self) -> ModpParameters<LIMBS> {
+        ModpParameters {
+            modulus: self.modulus,
+            order: *self.order,
+            generator: self.generator,
+        }
+    }
+
+    /// The modulus p
+    pub fn modulus(&self) -> Uint<LIMBS> {
+        self.modulus
+    }
+
+    /// The order q of the subgroup, modulus of exponents
+    pub fn order(&self) -> Uint<LIMBS> {
+        *self.order
+    }
+}
+
+impl<const LIMBS: usize> From<ModpGroup<LIMBS>> for ModpParameters<LIMBS> {
+    fn from(group: ModpGroup<LIMBS>) -> Self {
+        group.parameters()
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<ModpParameters<LIMBS>> for ModpGroup<LIMBS> {
+    type Error = ParameterError;
+
+    fn try_from(parameters: ModpParameters<LIMBS>) -> Result<Self, ParameterError> {
+        parameters.group()
+    }
+}
+
+impl<const LIMBS: usize> Group for ModpGroup<LIMBS> {
+    type Element = Uint<LIMBS>;
+    type Scalar = Uint<LIMBS>;
+
+    fn generator(&self) -> Uint<LIMBS> {
+        self.generator
+    }
+
+    fn identity(&self) -> Uint<LIMBS> {
+        <Uint<LIMBS>>::ONE
+    }
+
+    fn operate(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        self.rem_cac_cache.mul_mod(lhs, rhs)
+    }
+
+    fn invert(&self, element: &Uint<LIMBS>) -> Uint<LIMBS> {
+        self.rem_cac_cache.inv_mod(element)
+    }
+
+    fn exp(&self, base: &Uint<LIMBS>, exp: &Uint<LIMBS>) -> Uint<LIMBS> {
+        self.rem_cac_cache.pow_mod(base, exp)
+    }
+
+    fn validate_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(), ParameterError> {
+        validate_parameters_with_rng(&self.modulus, &self.order, &self.generator, rng)
+    }
+
+    fn contains(&self, element: &Uint<LIMBS>) -> bool {
+        // 0 < y < p and y^q = 1 (mod p), 0 is ruled out by the second check
+        *element < self.modulus
+            && self.rem_cac_cache.pow_mod(element, &self.order) == self.identity()
+    }
+
+    fn is_canonical_scalar(&self, scalar: &Uint<LIMBS>) -> bool {
+        scalar < &*self.order
+    }
+
+    fn reduce_scalar(&self, scalar: &Uint<LIMBS>) -> Uint<LIMBS> {
+        scalar.rem(&self.order)
+    }
+
+    fn encode_parameters(&self) -> Vec<u8> {
+        self.parameters().to_bytes()
+    }
+
+    fn element_len(&self) -> usize {
+        LIMBS * Limb::BYTES
+    }
+
+    fn encode_element(&self, element: &Uint<LIMBS>) -> Vec<u8> {
+        uint_to_bytes(element)
+    }
+
+    fn decode_element(&self, bytes: &[u8]) -> Option<Uint<LIMBS>> {
+        uint_from_bytes(bytes).filter(|element| *element < self.modulus)
+    }
+
+    fn scalar_len(&self) -> usize {
+        LIMBS * Limb::BYTES
+    }
+
+    fn encode_scalar(&self, scalar: &Uint<LIMBS>) -> Vec<u8> {
+        uint_to_bytes(scalar)
+    }
+
+    fn decode_scalar(&self, bytes: &[u8]) -> Option<Uint<LIMBS>> {
+        uint_from_bytes(bytes).filter(|scalar| self.is_canonical_scalar(scalar))
+    }
+
+    fn random_scalar(&self, rng: &mut impl CryptoRngCore) -> Uint<LIMBS> {
+        Uint::random_mod(rng, &self.order)
+    }
+
+    fn scalar_add(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        lhs.add_mod(rhs, &self.order)
+    }
+
+    fn scalar_mul(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        mul_mod_wide(lhs, rhs, &self.order)
+    }
+
+    fn scalar_sub(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        lhs.sub_mod(rhs, &self.order)
+    }
+
+    fn scalar_invert(&self, scalar: &Uint<LIMBS>) -> Option<Uint<LIMBS>> {
+        // q is an odd prime for every group worth using
+        let (inverse, invertible) = scalar.inv_odd_mod(&self.order);
+
+        bool::from(invertible).then_some(inverse)
+    }
+
+    fn scalar_from_u64(&self, value: u64) -> Uint<LIMBS> {
+        Uint::from_u64(value).rem(&self.order)
+    }
+
+    fn challenge_scalar(&self, transcript: &mut Transcript, label: &[u8]) -> Uint<LIMBS> {
+        transcript.challenge_uint(label, &self.order)
+    }
+}
+
+/// Check that (p, q, g) describe a group the proofs are meaningful in
+///
+/// - p and q pass Miller-Rabin
+/// - q divides p - 1
+/// - g is reduced, not 0, 1 or p - 1, and g^q = 1 (mod p), so g has order exactly q
+pub fn validate_parameters<const LIMBS: usize>(
+    modulus: &Uint<LIMBS>,
+    order: &Uint<LIMBS>,
+    generator: &Uint<LIMBS>,
+) -> Result<(), ParameterError> {
+    validate_parameters_with_rng(modulus, order, generator, &mut OsRng)
+}
+
+/// Same as [`validate_parameters`], drawing the Miller-Rabin bases from `rng`
+pub fn validate_parameters_with_rng<const LIMBS: usize>(
+    modulus: &Uint<LIMBS>,
+    order: &Uint<LIMBS>,
+    generator: &Uint<LIMBS>,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(), ParameterError> {
+    if !is_probable_prime(modulus, MILLER_RABIN_ROUNDS, rng) {
+        return Err(ParameterError::CompositeModulus);
+    }
+    if !is_probable_prime(order, MILLER_RABIN_ROUNDS, rng) {
+        return Err(ParameterError::CompositeOrder);
+    }
+
+    // q is prime, so it is safe to unwrap here
+    let p_minus_1 = modulus.wrapping_sub(&<Uint<LIMBS>>::ONE);
+    if p_minus_1.rem(&NonZero::new(*order).unwrap()) != <Uint<LIMBS>>::ZERO {
+        return Err(ParameterError::OrderNotDividingGroup);
+    }
+
+    if generator >= modulus {
+        return Err(ParameterError::GeneratorOutOfRange);
+    }
+    if *generator == <Uint<LIMBS>>::ZERO
+        || *generator == <Uint<LIMBS>>::ONE
+        || *generator == p_minus_1
+    {
+        return Err(ParameterError::DegenerateGenerator);
+    }
+
+    // g != 1 and g^q = 1 with q prime means the order of g is exactly q
+    let rem_cac_cache = RemCaculateCache::new(modulus);
+    if rem_cac_cache.pow_mod(generator, order) != <Uint<LIMBS>>::ONE {
+        return Err(ParameterError::WrongGeneratorOrder);
+    }
+
+    Ok(())
+}
+
+/// Wraps around a weird API from crypto-bigint to compute exponentiation with remainder
+///
+/// Precompute some stuff (?) for montgomery reduction and reuse it in each computation with same modulus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RemCaculateCache<const LIMBS: usize> {
+    dyn_residue_params: DynResidueParams<LIMBS>,
+}
+
+impl<const LIMBS: usize> RemCaculateCache<LIMBS> {
+    fn new(modulus: &Uint<LIMBS>) -> Self {
+        let dyn_residue_params = DynResidueParams::new(modulus);
+
+        Self { dyn_residue_params }
+    }
+
+    fn pow_mod(&self, base: &Uint<LIMBS>, exp: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let dyn_residue = DynResidue::new(base, self.dyn_residue_params);
+
+        dyn_residue.pow(exp).retrieve()
+    }
+
+    fn mul_mod(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let dyn_residue_lhs = DynResidue::new(lhs, self.dyn_residue_params);
+        let dyn_residue_rhs = DynResidue::new(rhs, self.dyn_residue_params);
+
+        dyn_residue_lhs.mul(&dyn_residue_rhs).retrieve()
+    }
+
+    // zero is returned for non invertible input, which never happens for residues coprime to p
+    fn inv_mod(&self, value: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let dyn_residue = DynResidue::new(value, self.dyn_residue_params);
+
+        dyn_residue.invert().0.retrieve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn test_arithmetic() {
+        // 2 has order 23 mod 47
+        let group =
+            ModpGroup::new(U256::from_u64(47), U256::from_u64(23), U256::from_u64(2)).unwrap();
+        let x = U256::from_u64(17);
+        let y = group.exp_generator(&x);
+
+        assert_eq!(y, U256::from_u64(36)); // 2^17 = 36 (mod 47)
+        assert_eq!(group.operate(&y, &group.invert(&y)), group.identity());
+        assert_eq!(
+            group.exp_generator(&group.scalar_add(&x, &U256::from_u64(6))),
+            group.identity() // 2^23 = 1 (mod 47)
+        );
+        assert_eq!(
+            group.scalar_mul(&x, &U256::from_u64(2)),
+            U256::from_u64(11) // 34 = 11 (mod 23)
+        );
+        assert_eq!(
+            group.scalar_sub(&U256::from_u64(6), &x),
+            U256::from_u64(12) // -11 = 12 (mod 23)
+        );
+        assert_eq!(
+            group.scalar_invert(&x),
+            Some(U256::from_u64(19)) // 17 * 19 = 323 = 14 * 23 + 1
+        );
+        assert_eq!(group.scalar_invert(&U256::ZERO), None);
+        assert_eq!(group.scalar_from_u64(24), U256::ONE);
+    }
+
+    #[test]
+    fn test_validate_parameters() {
+        let check = |p: u64, q: u64, g: u64| {
+            let mut rng = ChaCha20Rng::seed_from_u64(p);
+            let (p, q, g) = (U256::from_u64(p), U256::from_u64(q), U256::from_u64(g));
+
+            validate_parameters_with_rng(&p, &q, &g, &mut rng)
+        };
+
+        assert_eq!(check(47, 23, 2), Ok(()));
+        assert_eq!(check(2305843009213691579, 1152921504606845789, 4), Ok(()));
+        assert_eq!(check(45, 22, 2), Err(ParameterError::CompositeModulus));
+        assert_eq!(check(47, 22, 2), Err(ParameterError::CompositeOrder));
+        assert_eq!(check(47, 11, 2), Err(ParameterError::OrderNotDividingGroup));
+        assert_eq!(check(47, 23, 49), Err(ParameterError::GeneratorOutOfRange));
+        assert_eq!(check(47, 23, 0), Err(ParameterError::DegenerateGenerator));
+        assert_eq!(check(47, 23, 1), Err(ParameterError::DegenerateGenerator));
+        assert_eq!(check(47, 23, 46), Err(ParameterError::DegenerateGenerator));
+        assert_eq!(check(47, 23, 5), Err(ParameterError::WrongGeneratorOrder)); // order 46
+        assert_eq!(check(47, 2, 46), Err(ParameterError::DegenerateGenerator));
+    }
+
+    #[test]
+    fn test_new_checked() {
+        let group =
+            ModpGroup::new_checked(U256::from_u64(59), U256::from_u64(29), U256::from_u64(4));
+        assert!(group.unwrap().validate().is_ok());
+
+        let group =
+            ModpGroup::new_checked(U256::from_u64(31), U256::from_u64(30), U256::from_u64(3));
+        assert_eq!(group, Err(ParameterError::CompositeOrder));
+    }
+
+    #[test]
+    fn test_new() {
+        let new = |p: u64, q: u64| ModpGroup::new(U256::from_u64(p), U256::from_u64(q), U256::ONE);
+
+        assert!(new(47, 23).is_ok());
+        assert_eq!(new(1, 23), Err(ParameterError::CompositeModulus));
+        assert_eq!(new(46, 23), Err(ParameterError::CompositeModulus));
+        assert_eq!(new(47, 0), Err(ParameterError::CompositeOrder));
+    }
+
+    #[test]
+    fn test_parameters_bytes() {
+        let parameters = ModpParameters {
+            modulus: U256::from_u64(47),
+            order: U256::from_u64(23),
+            generator: U256::from_u64(2),
+        };
+        let bytes = parameters.to_bytes();
+
+        assert_eq!(bytes.len(), 96);
+        assert_eq!(bytes[31], 47);
+        assert_eq!(ModpParameters::from_bytes(&bytes), Some(parameters));
+        assert_eq!(
+            ModpParameters::<{ U256::LIMBS }>::from_bytes(&bytes[1..]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let group =
+            ModpGroup::new(U256::from_u64(47), U256::from_u64(23), U256::from_u64(2)).unwrap();
+
+        let element = uint_to_bytes(&U256::from_u64(46));
+        assert_eq!(group.decode_element(&element), Some(U256::from_u64(46)));
+        assert_eq!(
+            group.decode_element(&uint_to_bytes(&U256::from_u64(47))),
+            None
+        );
+        assert_eq!(group.decode_scalar(&element), None); // 46 >= q
+        assert_eq!(
+            group.decode_scalar(&uint_to_bytes(&U256::from_u64(22))),
+            Some(U256::from_u64(22))
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        let group =
+            ModpGroup::new(U256::from_u64(47), U256::from_u64(23), U256::from_u64(2)).unwrap();
+
+        assert!(group.contains(&U256::from_u64(36)));
+        assert!(group.contains(&U256::ONE));
+        assert!(!group.contains(&U256::ZERO));
+        assert!(!group.contains(&U256::from_u64(46))); // -1 has order 2
+        assert!(!group.contains(&U256::from_u64(5))); // 5 is a non-residue mod 47, order 46
+        assert!(!group.contains(&U256::from_u64(47 + 36))); // not reduced
+    }
+}
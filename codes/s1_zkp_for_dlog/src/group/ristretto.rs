@@ -0,0 +1,587 @@
+//! ristretto255, the prime order group built on top of edwards25519 (RFC 9496).
+//!
+//! edwards25519 is the twisted Edwards curve -x^2 + y^2 = 1 + d x^2 y^2 over the integers mod
+//! 2^255 - 19. It has 8 times as many points as its prime order subgroup, and a proof over it
+//! would have to deal with the small torsion. Ristretto instead encodes classes of 8 points
+//! differing by torsion as a single 32-byte string, so every encoding is one element of a
+//! group of prime order l and there is no cofactor left to clear.
+//!
+//! Points are kept and added in extended coordinates (X : Y : Z : T) with the complete formulas
+//! of Hisil, Wong, Carter and Dawson (2008), and only encoded on the way out.
+
+use std::fmt;
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::CryptoRngCore,
+    subtle::{Choice, ConditionallySelectable},
+    NonZero, RandomMod, U256,
+};
+
+use super::Group;
+use crate::{
+    encoding::FixedBytes,
+    mul_mod_wide,
+    primality::{is_probable_prime, MILLER_RABIN_ROUNDS},
+    ParameterError, Transcript,
+};
+
+/// The field modulus 2^255 - 19
+const MODULUS: U256 =
+    U256::from_be_hex("7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFED");
+
+/// The group order l = 2^252 + 27742317777372353535851937790883648493
+const ORDER: U256 =
+    U256::from_be_hex("1000000000000000000000000000000014DEF9DEA2F79CD65812631A5CF5D3ED");
+
+/// d = -121665 / 121666 of the curve equation
+const D: U256 =
+    U256::from_be_hex("52036CEE2B6FFE738CC740797779E89800700A4D4141D8AB75EB4DCA135978A3");
+
+/// 2d, used by the addition formulas
+const D2: U256 =
+    U256::from_be_hex("2406D9DC56DFFCE7198E80F2EEF3D13000E0149A8283B156EBD69B9426B2F159");
+
+/// sqrt(-1) = 2^((p - 1) / 4)
+const SQRT_M1: U256 =
+    U256::from_be_hex("2B8324804FC1DF0B2B4D00993DFBD7A72F431806AD2FE478C4EE1B274A0EA0B0");
+
+/// 1 / sqrt(a - d) with a = -1
+const INVSQRT_A_MINUS_D: U256 =
+    U256::from_be_hex("786C8905CFAFFCA216C27B91FE01D8409D2F16175A4172BE99C8FDAA805D40EA");
+
+/// The edwards25519 base point, y = 4 / 5 and x even
+const BASE_X: U256 =
+    U256::from_be_hex("216936D3CD6E53FEC0A4E231FDD6DC5C692CC7609525A7B2C9562D608F25D51A");
+const BASE_Y: U256 =
+    U256::from_be_hex("6666666666666666666666666666666666666666666666666666666666666658");
+
+// bytes of an encoded element or scalar
+const ENCODED_LEN: usize = 32;
+
+/// An element of ristretto255, stored as one of the points of edwards25519 it stands for
+///
+/// Equality compares the elements rather than the points, as in RFC 9496 section 4.5, and
+/// the canonical encoding is only computed when asked for.
+#[derive(Clone, Copy)]
+pub struct RistrettoPoint(EdwardsPoint);
+
+impl RistrettoPoint {
+    /// The canonical encoding, as in RFC 9496 section 4.3.2
+    pub fn to_bytes(&self) -> [u8; ENCODED_LEN] {
+        Ristretto255::new().encode(&self.0)
+    }
+}
+
+impl PartialEq for RistrettoPoint {
+    fn eq(&self, other: &Self) -> bool {
+        let (lhs, rhs) = (&self.0, &other.0);
+
+        lhs.x.mul(&rhs.y) == lhs.y.mul(&rhs.x) || lhs.y.mul(&rhs.y) == lhs.x.mul(&rhs.x)
+    }
+}
+
+impl Eq for RistrettoPoint {}
+
+impl ConditionallySelectable for RistrettoPoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(EdwardsPoint::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl fmt::Debug for RistrettoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex: String = self.to_bytes().iter().map(|b| format!("{b:02x}")).collect();
+
+        f.debug_tuple("RistrettoPoint").field(&hex).finish()
+    }
+}
+
+/// The canonical encoding, non-canonical bytes are rejected on the way in
+impl FixedBytes for RistrettoPoint {
+    fn to_fixed_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    fn from_fixed_bytes(bytes: &[u8]) -> Option<Self> {
+        Ristretto255::new().decode_element(bytes)
+    }
+}
+
+/// A point (X : Y : Z : T) of edwards25519 with x = X / Z, y = Y / Z and xy = T / Z
+#[derive(Clone, Copy, Debug)]
+struct EdwardsPoint {
+    x: DynResidue<{ U256::LIMBS }>,
+    y: DynResidue<{ U256::LIMBS }>,
+    z: DynResidue<{ U256::LIMBS }>,
+    t: DynResidue<{ U256::LIMBS }>,
+}
+
+impl ConditionallySelectable for EdwardsPoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: DynResidue::conditional_select(&a.x, &b.x, choice),
+            y: DynResidue::conditional_select(&a.y, &b.y, choice),
+            z: DynResidue::conditional_select(&a.z, &b.z, choice),
+            t: DynResidue::conditional_select(&a.t, &b.t, choice),
+        }
+    }
+}
+
+/// The ristretto255 group, with the image of the edwards25519 base point as generator
+///
+/// Exponents and responses live mod l. Elements and scalars are encoded little-endian as
+/// everywhere around Curve25519, a proof round is 32 + 32 bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ristretto255 {
+    field: DynResidueParams<{ U256::LIMBS }>,
+    order: NonZero<U256>,
+    d2: DynResidue<{ U256::LIMBS }>,
+}
+
+impl Ristretto255 {
+    /// The group, with the field arithmetic mod 2^255 - 19 set up
+    pub fn new() -> Self {
+        let field = DynResidueParams::new(&MODULUS);
+
+        Self {
+            field,
+            order: NonZero::new(ORDER).unwrap(),
+            d2: DynResidue::new(&D2, field),
+        }
+    }
+
+    /// The order l of the group, modulus of exponents
+    pub fn order(&self) -> U256 {
+        *self.order
+    }
+
+    fn base_point(&self) -> EdwardsPoint {
+        let x = self.field_element(&BASE_X);
+        let y = self.field_element(&BASE_Y);
+
+        EdwardsPoint {
+            x,
+            y,
+            z: self.small(1),
+            t: x.mul(&y),
+        }
+    }
+
+    fn identity_point(&self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: self.small(0),
+            y: self.small(1),
+            z: self.small(1),
+            t: self.small(0),
+        }
+    }
+
+    /// lhs + rhs, "add-2008-hwcd-3" for a = -1, complete on edwards25519
+    fn add(&self, lhs: &EdwardsPoint, rhs: &EdwardsPoint) -> EdwardsPoint {
+        let a = lhs.y.sub(&lhs.x).mul(&rhs.y.sub(&rhs.x));
+        let b = lhs.y.add(&lhs.x).mul(&rhs.y.add(&rhs.x));
+        let c = lhs.t.mul(&self.d2).mul(&rhs.t);
+        let d = lhs.z.add(&lhs.z).mul(&rhs.z);
+        let (e, f, g, h) = (b.sub(&a), d.sub(&c), d.add(&c), b.add(&a));
+
+        EdwardsPoint {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+
+    /// scalar * point, with one doubling and one addition for every bit of `scalar`
+    ///
+    /// Both are computed whatever the bit, which only picks the result.
+    fn mul(&self, point: &EdwardsPoint, scalar: &U256) -> EdwardsPoint {
+        let mut result = self.identity_point();
+        for i in (0..U256::BITS).rev() {
+            result = self.add(&result, &result);
+
+            let sum = self.add(&result, point);
+            result = EdwardsPoint::conditional_select(&result, &sum, scalar.bit(i).into());
+        }
+
+        result
+    }
+
+    /// RFC 9496 section 4.3.2
+    fn encode(&self, point: &EdwardsPoint) -> [u8; ENCODED_LEN] {
+        let EdwardsPoint {
+            x: x0,
+            y: y0,
+            z: z0,
+            t: t0,
+        } = point;
+
+        let u1 = z0.add(y0).mul(&z0.sub(y0));
+        let u2 = x0.mul(y0);
+        let (_, invsqrt) = self.sqrt_ratio_m1(&self.small(1), &u1.mul(&u2.square()));
+        let den1 = invsqrt.mul(&u1);
+        let den2 = invsqrt.mul(&u2);
+        let z_inv = den1.mul(&den2).mul(t0);
+
+        let sqrt_m1 = self.field_element(&SQRT_M1);
+        let (x, y, den_inv) = if is_negative(&t0.mul(&z_inv)) {
+            let enchanted_denominator = den1.mul(&self.field_element(&INVSQRT_A_MINUS_D));
+            (y0.mul(&sqrt_m1), x0.mul(&sqrt_m1), enchanted_denominator)
+        } else {
+            (*x0, *y0, den2)
+        };
+        let y = if is_negative(&x.mul(&z_inv)) {
+            y.neg()
+        } else {
+            y
+        };
+
+        let s = abs(&den_inv.mul(&z0.sub(&y))).retrieve();
+        to_le_bytes(&s)
+    }
+
+    /// RFC 9496 section 4.3.1, `None` for anything but a canonical encoding
+    fn decode(&self, bytes: &[u8]) -> Option<EdwardsPoint> {
+        let bytes: [u8; ENCODED_LEN] = bytes.try_into().ok()?;
+        let s = U256::from_le_slice(&bytes);
+        if s >= MODULUS {
+            return None;
+        }
+        let s = self.field_element(&s);
+        if is_negative(&s) {
+            return None;
+        }
+
+        let ss = s.square();
+        let u1 = self.small(1).sub(&ss);
+        let u2 = self.small(1).add(&ss);
+        let u2_sqr = u2.square();
+        let v = self.field_element(&D).mul(&u1.square()).neg().sub(&u2_sqr);
+
+        let (was_square, invsqrt) = self.sqrt_ratio_m1(&self.small(1), &v.mul(&u2_sqr));
+        let den_x = invsqrt.mul(&u2);
+        let den_y = invsqrt.mul(&den_x).mul(&v);
+
+        let x = abs(&s.add(&s).mul(&den_x));
+        let y = u1.mul(&den_y);
+        let t = x.mul(&y);
+        if !was_square || is_negative(&t) || y == self.small(0) {
+            return None;
+        }
+
+        Some(EdwardsPoint {
+            x,
+            y,
+            z: self.small(1),
+            t,
+        })
+    }
+
+    /// (whether u / v is a square, sqrt(u / v) or sqrt(i * u / v)), RFC 9496 section 4.2
+    fn sqrt_ratio_m1(
+        &self,
+        u: &DynResidue<{ U256::LIMBS }>,
+        v: &DynResidue<{ U256::LIMBS }>,
+    ) -> (bool, DynResidue<{ U256::LIMBS }>) {
+        let sqrt_m1 = self.field_element(&SQRT_M1);
+        let v3 = v.square().mul(v);
+        let v7 = v3.square().mul(v);
+
+        // (p - 5) / 8 = (p >> 3) since p = 5 (mod 8)
+        let r = u.mul(&v3).mul(&u.mul(&v7).pow(&MODULUS.shr_vartime(3)));
+        let check = v.mul(&r.square());
+
+        let correct_sign = check == *u;
+        let flipped_sign = check == u.neg();
+        let flipped_sign_i = check == u.neg().mul(&sqrt_m1);
+        let r = if flipped_sign || flipped_sign_i {
+            r.mul(&sqrt_m1)
+        } else {
+            r
+        };
+
+        (correct_sign || flipped_sign, abs(&r))
+    }
+
+    fn field_element(&self, value: &U256) -> DynResidue<{ U256::LIMBS }> {
+        DynResidue::new(value, self.field)
+    }
+
+    fn small(&self, value: u8) -> DynResidue<{ U256::LIMBS }> {
+        self.field_element(&U256::from_u8(value))
+    }
+}
+
+impl Default for Ristretto255 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Group for Ristretto255 {
+    type Element = RistrettoPoint;
+    type Scalar = U256;
+
+    // there are no parameters to choose, this only checks that l is prime and that the
+    // generator has order l
+    fn validate_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(), ParameterError> {
+        if !is_probable_prime(&ORDER, MILLER_RABIN_ROUNDS, rng) {
+            return Err(ParameterError::CompositeOrder);
+        }
+        if self.exp_generator(&ORDER) != self.identity() || self.generator() == self.identity() {
+            return Err(ParameterError::WrongGeneratorOrder);
+        }
+
+        Ok(())
+    }
+
+    fn generator(&self) -> RistrettoPoint {
+        RistrettoPoint(self.base_point())
+    }
+
+    fn identity(&self) -> RistrettoPoint {
+        RistrettoPoint(self.identity_point())
+    }
+
+    fn operate(&self, lhs: &RistrettoPoint, rhs: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(self.add(&lhs.0, &rhs.0))
+    }
+
+    fn invert(&self, element: &RistrettoPoint) -> RistrettoPoint {
+        // -(x, y) = (-x, y)
+        let point = element.0;
+
+        RistrettoPoint(EdwardsPoint {
+            x: point.x.neg(),
+            t: point.t.neg(),
+            ..point
+        })
+    }
+
+    fn exp(&self, base: &RistrettoPoint, exp: &U256) -> RistrettoPoint {
+        RistrettoPoint(self.mul(&base.0, exp))
+    }
+
+    fn contains(&self, _element: &RistrettoPoint) -> bool {
+        // elements only come from decoding a canonical encoding or from the group operations,
+        // so each of them stands for an element of the prime order group
+        true
+    }
+
+    fn is_canonical_scalar(&self, scalar: &U256) -> bool {
+        scalar < &*self.order
+    }
+
+    fn reduce_scalar(&self, scalar: &U256) -> U256 {
+        scalar.rem(&self.order)
+    }
+
+    fn encode_parameters(&self) -> Vec<u8> {
+        b"ristretto255".to_vec()
+    }
+
+    fn element_len(&self) -> usize {
+        ENCODED_LEN
+    }
+
+    fn encode_element(&self, element: &RistrettoPoint) -> Vec<u8> {
+        self.encode(&element.0).to_vec()
+    }
+
+    fn decode_element(&self, bytes: &[u8]) -> Option<RistrettoPoint> {
+        self.decode(bytes).map(RistrettoPoint)
+    }
+
+    fn scalar_len(&self) -> usize {
+        ENCODED_LEN
+    }
+
+    fn encode_scalar(&self, scalar: &U256) -> Vec<u8> {
+        to_le_bytes(scalar).to_vec()
+    }
+
+    fn decode_scalar(&self, bytes: &[u8]) -> Option<U256> {
+        if bytes.len() != ENCODED_LEN {
+            return None;
+        }
+
+        Some(U256::from_le_slice(bytes)).filter(|scalar| self.is_canonical_scalar(scalar))
+    }
+
+    fn random_scalar(&self, rng: &mut impl CryptoRngCore) -> U256 {
+        U256::random_mod(rng, &self.order)
+    }
+
+    fn scalar_add(&self, lhs: &U256, rhs: &U256) -> U256 {
+        lhs.add_mod(rhs, &self.order)
+    }
+
+    fn scalar_mul(&self, lhs: &U256, rhs: &U256) -> U256 {
+        mul_mod_wide(lhs, rhs, &self.order)
+    }
+
+    fn scalar_sub(&self, lhs: &U256, rhs: &U256) -> U256 {
+        lhs.sub_mod(rhs, &self.order)
+    }
+
+    fn scalar_invert(&self, scalar: &U256) -> Option<U256> {
+        let (inverse, invertible) = scalar.inv_odd_mod(&self.order);
+
+        bool::from(invertible).then_some(inverse)
+    }
+
+    fn scalar_from_u64(&self, value: u64) -> U256 {
+        U256::from_u64(value).rem(&self.order)
+    }
+
+    fn challenge_scalar(&self, transcript: &mut Transcript, label: &[u8]) -> U256 {
+        transcript.challenge_uint(label, &self.order)
+    }
+}
+
+// the low bit of the reduced value, "negative" in RFC 9496
+fn is_negative(value: &DynResidue<{ U256::LIMBS }>) -> bool {
+    value.retrieve().bit_vartime(0)
+}
+
+fn abs(value: &DynResidue<{ U256::LIMBS }>) -> DynResidue<{ U256::LIMBS }> {
+    if is_negative(value) {
+        value.neg()
+    } else {
+        *value
+    }
+}
+
+fn to_le_bytes(value: &U256) -> [u8; ENCODED_LEN] {
+    let mut bytes = [0; ENCODED_LEN];
+    for (chunk, word) in bytes.chunks_mut(8).zip(value.as_words()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // encodings of 0, B, 2B, ..., 15B, RFC 9496 appendix A.1
+    const MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+        "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+        "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+        "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+        "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+        "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+        "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+        "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+        "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+        "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+        "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+        "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+        "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+        "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+    ];
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_multiples_of_generator() {
+        let group = Ristretto255::new();
+
+        let mut multiple = group.identity();
+        for (i, expected) in MULTIPLES.into_iter().enumerate() {
+            assert_eq!(group.encode_element(&multiple), bytes(expected));
+            assert_eq!(group.exp_generator(&U256::from_u64(i as u64)), multiple);
+            assert_eq!(group.decode_element(&bytes(expected)), Some(multiple));
+
+            multiple = group.operate(&multiple, &group.generator());
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let group = Ristretto255::new();
+        let g = group.generator();
+        let l_minus_1 = ORDER.wrapping_sub(&U256::ONE);
+
+        assert_eq!(group.exp_generator(&ORDER), group.identity());
+        assert_eq!(group.exp_generator(&l_minus_1), group.invert(&g));
+        assert_eq!(group.operate(&g, &group.invert(&g)), group.identity());
+        assert_eq!(
+            group.exp(&group.exp_generator(&U256::from_u64(3)), &U256::from_u64(5)),
+            group.decode_element(&bytes(MULTIPLES[15])).unwrap()
+        );
+        assert_eq!(
+            group.validate_with_rng(&mut ChaCha20Rng::seed_from_u64(0)),
+            Ok(())
+        );
+        assert_eq!(
+            group.field_element(&D2),
+            group.field_element(&D).mul(&group.small(2))
+        );
+    }
+
+    #[test]
+    fn test_equality_of_classes() {
+        let group = Ristretto255::new();
+        let point = group.exp_generator(&U256::from_u64(7)).0;
+
+        // (x, y) and (-x, -y) differ by the 2-torsion point (0, -1), same element
+        let other = RistrettoPoint(EdwardsPoint {
+            x: point.x.neg(),
+            y: point.y.neg(),
+            ..point
+        });
+        assert_eq!(other, RistrettoPoint(point));
+        assert_eq!(other.to_bytes(), RistrettoPoint(point).to_bytes());
+        assert_ne!(other, group.exp_generator(&U256::from_u64(8)));
+    }
+
+    #[test]
+    fn test_reject_non_canonical() {
+        let group = Ristretto255::new();
+
+        for hex in [
+            // s >= p
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            // s negative, i.e. odd
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            // the high bit is never set
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            // no point has this s, RFC 9496 appendix A.2
+            "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+            "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+            "de6a7b00deadc788eb6b6c8d20c0ae96c2f2019078fa604fee5b87d6e989ad7b",
+            "bcab477be20861e01e4a0e295284146a510150d9817763caf1a6f4b422d67042",
+        ] {
+            assert_eq!(group.decode_element(&bytes(hex)), None, "{hex}");
+        }
+
+        assert_eq!(group.decode_element(&[0; 31]), None);
+    }
+
+    #[test]
+    fn test_scalar_encoding() {
+        let group = Ristretto255::new();
+        let mut bytes = group.encode_scalar(&U256::from_u64(0x0102));
+
+        assert_eq!(bytes[..2], [0x02, 0x01]);
+        assert_eq!(group.decode_scalar(&bytes), Some(U256::from_u64(0x0102)));
+
+        bytes.copy_from_slice(&to_le_bytes(&ORDER));
+        assert_eq!(group.decode_scalar(&bytes), None);
+    }
+}
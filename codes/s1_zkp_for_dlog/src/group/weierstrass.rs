@@ -0,0 +1,740 @@
+//! Prime order short Weierstrass curves y^2 = x^3 + ax + b over the integers mod p.
+//!
+//! Points are stored in affine coordinates and added in projective ones, with the complete
+//! formulas of Renes, Costello and Batina (2016): no special case for doubling or the identity.
+//! Elements are sent as SEC1 compressed points, so a proof round is 33 + 32 bytes on a
+//! 256-bit curve instead of twice the size of a modulus.
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::{CryptoRngCore, OsRng},
+    subtle::{Choice, ConditionallySelectable},
+    Limb, NonZero, RandomMod, Uint,
+};
+
+use super::Group;
+use crate::{
+    encoding::{uint_from_bytes, uint_to_bytes, FixedBytes},
+    mul_mod_wide,
+    primality::{is_probable_prime, MILLER_RABIN_ROUNDS},
+    ParameterError, Transcript,
+};
+
+// SEC1 tags of compressed points with even and odd y, and of uncompressed points
+const TAG_EVEN: u8 = 0x02;
+const TAG_ODD: u8 = 0x03;
+const TAG_UNCOMPRESSED: u8 = 0x04;
+
+/// Raw parameters (p, a, b, G, n) of a [`WeierstrassCurve`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurveParameters<const LIMBS: usize> {
+    pub modulus: Uint<LIMBS>,     // p
+    pub a: Uint<LIMBS>,           // a
+    pub b: Uint<LIMBS>,           // b
+    pub generator_x: Uint<LIMBS>, // x of G
+    pub generator_y: Uint<LIMBS>, // y of G
+    pub order: Uint<LIMBS>,       // n
+}
+
+impl<const LIMBS: usize> CurveParameters<LIMBS> {
+    /// Precompute the curve of these parameters, see [`WeierstrassCurve::new`] for what is checked
+    pub fn curve(&self) -> Result<WeierstrassCurve<LIMBS>, ParameterError> {
+        WeierstrassCurve::new(*self)
+    }
+
+    /// Check that the parameters describe a curve the proofs are meaningful on
+    ///
+    /// - p and n pass Miller-Rabin
+    /// - the curve is not singular, 4a^3 + 27b^2 != 0 (mod p)
+    /// - G lies on the curve and nG is the identity, so G has order exactly n
+    /// - n is larger than half the number of points (Hasse), so G generates every point
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        self.validate_with_rng(&mut OsRng)
+    }
+
+    /// Same as [`Self::validate`], drawing the Miller-Rabin bases from `rng`
+    pub fn validate_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(), ParameterError> {
+        let curve = self.curve()?;
+
+        if !is_probable_prime(&self.modulus, MILLER_RABIN_ROUNDS, rng) {
+            return Err(ParameterError::CompositeModulus);
+        }
+        if !is_probable_prime(&self.order, MILLER_RABIN_ROUNDS, rng) {
+            return Err(ParameterError::CompositeOrder);
+        }
+
+        let a = curve.field_element(&self.a);
+        let b = curve.field_element(&self.b);
+        let discriminant = curve
+            .small(4)
+            .mul(&a.square().mul(&a))
+            .add(&curve.small(27).mul(&b.square()));
+        if discriminant == curve.small(0) {
+            return Err(ParameterError::SingularCurve);
+        }
+
+        if !curve.is_on_curve(&self.generator_x, &self.generator_y) {
+            return Err(ParameterError::GeneratorNotOnCurve);
+        }
+        let generator = curve.to_projective(&curve.generator);
+        if curve.to_affine(&curve.mul(&generator, &self.order)) != AffinePoint::Identity {
+            return Err(ParameterError::WrongGeneratorOrder);
+        }
+
+        // the curve has at most p + 1 + 2 sqrt(p) points, fewer than 2n when n exceeds this bound
+        let bound = self
+            .modulus
+            .shr_vartime(1)
+            .wrapping_add(&self.modulus.sqrt_vartime())
+            .wrapping_add(&Uint::from_u8(2));
+        if self.order <= bound {
+            return Err(ParameterError::CofactorNotOne);
+        }
+
+        Ok(())
+    }
+
+    /// `p || a || b || x || y || n`, each fixed-width big-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            self.modulus,
+            self.a,
+            self.b,
+            self.generator_x,
+            self.generator_y,
+            self.order,
+        ]
+        .iter()
+        .flat_map(uint_to_bytes)
+        .collect()
+    }
+}
+
+/// A point in affine coordinates, the element type of [`WeierstrassCurve`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AffinePoint<const LIMBS: usize> {
+    /// The point at infinity, neutral element of the curve
+    Identity,
+    /// (x, y), on the curve when it comes out of [`WeierstrassCurve`]
+    Coordinates { x: Uint<LIMBS>, y: Uint<LIMBS> },
+}
+
+/// Selects the coordinates without branching, only whether the result is the identity shows
+impl<const LIMBS: usize> ConditionallySelectable for AffinePoint<LIMBS> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let parts = |point: &Self| match *point {
+            AffinePoint::Identity => (Uint::ZERO, Uint::ZERO, 1),
+            AffinePoint::Coordinates { x, y } => (x, y, 0),
+        };
+        let ((a_x, a_y, a_identity), (b_x, b_y, b_identity)) = (parts(a), parts(b));
+
+        if u8::conditional_select(&a_identity, &b_identity, choice) == 1 {
+            AffinePoint::Identity
+        } else {
+            AffinePoint::Coordinates {
+                x: Uint::conditional_select(&a_x, &b_x, choice),
+                y: Uint::conditional_select(&a_y, &b_y, choice),
+            }
+        }
+    }
+}
+
+/// SEC1 uncompressed `04 || x || y`, the identity as zero bytes of the same length
+///
+/// Decompressing needs the curve, which serde does not have, so both coordinates are sent.
+/// Whether the point is on the curve is left to [`Group::contains`].
+impl<const LIMBS: usize> FixedBytes for AffinePoint<LIMBS> {
+    fn to_fixed_bytes(&self) -> Vec<u8> {
+        match self {
+            AffinePoint::Identity => vec![0; 1 + 2 * LIMBS * Limb::BYTES],
+            AffinePoint::Coordinates { x, y } => {
+                [vec![TAG_UNCOMPRESSED], uint_to_bytes(x), uint_to_bytes(y)].concat()
+            }
+        }
+    }
+
+    fn from_fixed_bytes(bytes: &[u8]) -> Option<Self> {
+        let (&tag, coordinates) = bytes.split_first()?;
+        if coordinates.len() != 2 * LIMBS * Limb::BYTES {
+            return None;
+        }
+        let (x, y) = coordinates.split_at(LIMBS * Limb::BYTES);
+        let (x, y) = (uint_from_bytes(x)?, uint_from_bytes(y)?);
+
+        match tag {
+            0 if x == Uint::ZERO && y == Uint::ZERO => Some(AffinePoint::Identity),
+            TAG_UNCOMPRESSED => Some(AffinePoint::Coordinates { x, y }),
+            _ => None,
+        }
+    }
+}
+
+/// A point (X : Y : Z) standing for (X / Z, Y / Z), the identity has Z = 0
+///
+/// Coordinates are kept in Montgomery form mod p, arithmetic goes through [`WeierstrassCurve`].
+#[derive(Clone, Copy, Debug)]
+pub struct ProjectivePoint<const LIMBS: usize> {
+    x: DynResidue<LIMBS>,
+    y: DynResidue<LIMBS>,
+    z: DynResidue<LIMBS>,
+}
+
+impl<const LIMBS: usize> ConditionallySelectable for ProjectivePoint<LIMBS> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: DynResidue::conditional_select(&a.x, &b.x, choice),
+            y: DynResidue::conditional_select(&a.y, &b.y, choice),
+            z: DynResidue::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+/// The points of a curve of prime order n, with G as generator
+///
+/// Exponents and responses live mod n. "Exponentiation" is scalar multiplication and the group
+/// operation is point addition, the [`Group`] trait keeps the multiplicative names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeierstrassCurve<const LIMBS: usize> {
+    parameters: CurveParameters<LIMBS>,
+    field: DynResidueParams<LIMBS>,
+    order: NonZero<Uint<LIMBS>>,
+    generator: AffinePoint<LIMBS>,
+    a: DynResidue<LIMBS>,
+    b: DynResidue<LIMBS>,
+    b3: DynResidue<LIMBS>, // 3b, used by the addition formulas
+    sqrt_exp: Uint<LIMBS>, // (p + 1) / 4
+}
+
+impl<const LIMBS: usize> WeierstrassCurve<LIMBS> {
+    /// Curve of the given parameters, where G is expected to have prime order n
+    ///
+    /// Only what the arithmetic relies on is checked (p odd with p = 3 (mod 4) for square roots,
+    /// n > 0, G reduced mod p), use [`CurveParameters::validate`] for untrusted parameters.
+    pub fn new(parameters: CurveParameters<LIMBS>) -> Result<Self, ParameterError> {
+        let CurveParameters {
+            modulus,
+            a,
+            b,
+            generator_x,
+            generator_y,
+            order,
+        } = parameters;
+
+        // montgomery reduction needs an odd modulus
+        if modulus <= <Uint<LIMBS>>::ONE || !modulus.bit_vartime(0) {
+            return Err(ParameterError::CompositeModulus);
+        }
+        if !modulus.bit_vartime(1) {
+            return Err(ParameterError::UnsupportedModulus);
+        }
+        let order = Option::from(NonZero::new(order)).ok_or(ParameterError::CompositeOrder)?;
+        if generator_x >= modulus || generator_y >= modulus {
+            return Err(ParameterError::GeneratorOutOfRange);
+        }
+
+        let field = DynResidueParams::new(&modulus);
+        let b = DynResidue::new(&b, field);
+
+        Ok(Self {
+            parameters,
+            field,
+            order,
+            generator: AffinePoint::Coordinates {
+                x: generator_x,
+                y: generator_y,
+            },
+            a: DynResidue::new(&a, field),
+            b,
+            b3: b.add(&b).add(&b),
+            sqrt_exp: modulus.shr_vartime(2).wrapping_add(&Uint::ONE),
+        })
+    }
+
+    /// The parameters (p, a, b, G, n) of this curve
+    pub fn parameters(&self) -> CurveParameters<LIMBS> {
+        self.parameters
+    }
+
+    /// The order n of the curve, modulus of exponents
+    pub fn order(&self) -> Uint<LIMBS> {
+        *self.order
+    }
+
+    /// The same point in projective coordinates
+    pub fn to_projective(&self, point: &AffinePoint<LIMBS>) -> ProjectivePoint<LIMBS> {
+        match point {
+            AffinePoint::Identity => ProjectivePoint {
+                x: self.small(0),
+                y: self.small(1),
+                z: self.small(0),
+            },
+            AffinePoint::Coordinates { x, y } => ProjectivePoint {
+                x: self.field_element(x),
+                y: self.field_element(y),
+                z: self.small(1),
+            },
+        }
+    }
+
+    /// The same point in affine coordinates, one inversion mod p
+    pub fn to_affine(&self, point: &ProjectivePoint<LIMBS>) -> AffinePoint<LIMBS> {
+        let (z_inverse, invertible) = point.z.invert();
+        if !bool::from(invertible) {
+            return AffinePoint::Identity;
+        }
+
+        AffinePoint::Coordinates {
+            x: point.x.mul(&z_inverse).retrieve(),
+            y: point.y.mul(&z_inverse).retrieve(),
+        }
+    }
+
+    /// lhs + rhs, algorithm 1 of Renes, Costello and Batina, complete for any a
+    pub fn add(
+        &self,
+        lhs: &ProjectivePoint<LIMBS>,
+        rhs: &ProjectivePoint<LIMBS>,
+    ) -> ProjectivePoint<LIMBS> {
+        let ProjectivePoint {
+            x: x1,
+            y: y1,
+            z: z1,
+        } = lhs;
+        let ProjectivePoint {
+            x: x2,
+            y: y2,
+            z: z2,
+        } = rhs;
+        let (a, b3) = (&self.a, &self.b3);
+
+        let t0 = x1.mul(x2);
+        let t1 = y1.mul(y2);
+        let t2 = z1.mul(z2);
+        let t3 = x1.add(y1).mul(&x2.add(y2)).sub(&t0.add(&t1));
+        let t4 = x1.add(z1).mul(&x2.add(z2)).sub(&t0.add(&t2));
+        let t5 = y1.add(z1).mul(&y2.add(z2)).sub(&t1.add(&t2));
+
+        let z3 = b3.mul(&t2).add(&a.mul(&t4));
+        let x3 = t1.sub(&z3);
+        let z3 = t1.add(&z3);
+        let y3 = x3.mul(&z3);
+
+        let t1 = t0.add(&t0).add(&t0).add(&a.mul(&t2));
+        let t2 = a.mul(&t0.sub(&a.mul(&t2)));
+        let t4 = b3.mul(&t4).add(&t2);
+
+        ProjectivePoint {
+            x: x3.mul(&t3).sub(&t5.mul(&t4)),
+            y: y3.add(&t1.mul(&t4)),
+            z: z3.mul(&t5).add(&t3.mul(&t1)),
+        }
+    }
+
+    /// 2 * point
+    pub fn double(&self, point: &ProjectivePoint<LIMBS>) -> ProjectivePoint<LIMBS> {
+        self.add(point, point)
+    }
+
+    /// scalar * point, with one doubling and one addition for every bit of `scalar`
+    ///
+    /// Both are computed whatever the bit, which only picks the result, so the running time
+    /// does not depend on a secret scalar.
+    pub fn mul(
+        &self,
+        point: &ProjectivePoint<LIMBS>,
+        scalar: &Uint<LIMBS>,
+    ) -> ProjectivePoint<LIMBS> {
+        let mut result = self.to_projective(&AffinePoint::Identity);
+        for i in (0..Uint::<LIMBS>::BITS).rev() {
+            result = self.double(&result);
+
+            let sum = self.add(&result, point);
+            result = ProjectivePoint::conditional_select(&result, &sum, scalar.bit(i).into());
+        }
+
+        result
+    }
+
+    /// Whether (x, y) is reduced and satisfies y^2 = x^3 + ax + b
+    fn is_on_curve(&self, x: &Uint<LIMBS>, y: &Uint<LIMBS>) -> bool {
+        let modulus = &self.parameters.modulus;
+        if x >= modulus || y >= modulus {
+            return false;
+        }
+
+        self.field_element(y).square() == self.right_hand_side(&self.field_element(x))
+    }
+
+    // x^3 + ax + b
+    fn right_hand_side(&self, x: &DynResidue<LIMBS>) -> DynResidue<LIMBS> {
+        x.square().mul(x).add(&self.a.mul(x)).add(&self.b)
+    }
+
+    fn field_element(&self, value: &Uint<LIMBS>) -> DynResidue<LIMBS> {
+        DynResidue::new(value, self.field)
+    }
+
+    fn small(&self, value: u8) -> DynResidue<LIMBS> {
+        self.field_element(&Uint::from_u8(value))
+    }
+}
+
+impl<const LIMBS: usize> From<WeierstrassCurve<LIMBS>> for CurveParameters<LIMBS> {
+    fn from(curve: WeierstrassCurve<LIMBS>) -> Self {
+        curve.parameters()
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<CurveParameters<LIMBS>> for WeierstrassCurve<LIMBS> {
+    type Error = ParameterError;
+
+    fn try_from(parameters: CurveParameters<LIMBS>) -> Result<Self, ParameterError> {
+        parameters.curve()
+    }
+}
+
+impl<const LIMBS: usize> Group for WeierstrassCurve<LIMBS> {
+    type Element = AffinePoint<LIMBS>;
+    type Scalar = Uint<LIMBS>;
+
+    fn validate_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(), ParameterError> {
+        self.parameters.validate_with_rng(rng)
+    }
+
+    fn generator(&self) -> AffinePoint<LIMBS> {
+        self.generator
+    }
+
+    fn identity(&self) -> AffinePoint<LIMBS> {
+        AffinePoint::Identity
+    }
+
+    fn operate(&self, lhs: &AffinePoint<LIMBS>, rhs: &AffinePoint<LIMBS>) -> AffinePoint<LIMBS> {
+        let sum = self.add(&self.to_projective(lhs), &self.to_projective(rhs));
+
+        self.to_affine(&sum)
+    }
+
+    fn invert(&self, element: &AffinePoint<LIMBS>) -> AffinePoint<LIMBS> {
+        match element {
+            AffinePoint::Identity => AffinePoint::Identity,
+            AffinePoint::Coordinates { x, y } => AffinePoint::Coordinates {
+                x: *x,
+                y: self.field_element(y).neg().retrieve(),
+            },
+        }
+    }
+
+    fn exp(&self, base: &AffinePoint<LIMBS>, exp: &Uint<LIMBS>) -> AffinePoint<LIMBS> {
+        self.to_affine(&self.mul(&self.to_projective(base), exp))
+    }
+
+    fn contains(&self, element: &AffinePoint<LIMBS>) -> bool {
+        // the curve has prime order, every point on it is in the group generated by G
+        match element {
+            AffinePoint::Identity => true,
+            AffinePoint::Coordinates { x, y } => self.is_on_curve(x, y),
+        }
+    }
+
+    fn is_canonical_scalar(&self, scalar: &Uint<LIMBS>) -> bool {
+        scalar < &*self.order
+    }
+
+    fn reduce_scalar(&self, scalar: &Uint<LIMBS>) -> Uint<LIMBS> {
+        scalar.rem(&self.order)
+    }
+
+    fn encode_parameters(&self) -> Vec<u8> {
+        self.parameters.to_bytes()
+    }
+
+    fn element_len(&self) -> usize {
+        1 + LIMBS * Limb::BYTES
+    }
+
+    /// SEC1 compressed point `tag || x`, tag 2 for even y and 3 for odd y
+    ///
+    /// SEC1 encodes the identity as the single byte 0, it is padded with zeros to the fixed width.
+    fn encode_element(&self, element: &AffinePoint<LIMBS>) -> Vec<u8> {
+        match element {
+            AffinePoint::Identity => vec![0; self.element_len()],
+            AffinePoint::Coordinates { x, y } => {
+                let tag = if y.bit_vartime(0) { TAG_ODD } else { TAG_EVEN };
+
+                let mut bytes = vec![tag];
+                bytes.extend(uint_to_bytes(x));
+                bytes
+            }
+        }
+    }
+
+    fn decode_element(&self, bytes: &[u8]) -> Option<AffinePoint<LIMBS>> {
+        let (&tag, x_bytes) = bytes.split_first()?;
+        let x = uint_from_bytes::<LIMBS>(x_bytes)?;
+
+        match tag {
+            0 if x == Uint::ZERO => Some(AffinePoint::Identity),
+            TAG_EVEN | TAG_ODD if x < self.parameters.modulus => {
+                // y = (x^3 + ax + b)^((p + 1) / 4), a square root if there is one since p = 3 (mod 4)
+                let rhs = self.right_hand_side(&self.field_element(&x));
+                let root = rhs.pow(&self.sqrt_exp);
+                if root.square() != rhs {
+                    return None;
+                }
+
+                let root = root.retrieve();
+                let y = if root.bit_vartime(0) == (tag == TAG_ODD) {
+                    root
+                } else {
+                    self.field_element(&root).neg().retrieve()
+                };
+
+                // y = 0 has no odd twin, tag 3 with it is not canonical
+                (y.bit_vartime(0) == (tag == TAG_ODD)).then_some(AffinePoint::Coordinates { x, y })
+            }
+            _ => None,
+        }
+    }
+
+    fn scalar_len(&self) -> usize {
+        LIMBS * Limb::BYTES
+    }
+
+    fn encode_scalar(&self, scalar: &Uint<LIMBS>) -> Vec<u8> {
+        uint_to_bytes(scalar)
+    }
+
+    fn decode_scalar(&self, bytes: &[u8]) -> Option<Uint<LIMBS>> {
+        uint_from_bytes(bytes).filter(|scalar| self.is_canonical_scalar(scalar))
+    }
+
+    fn random_scalar(&self, rng: &mut impl CryptoRngCore) -> Uint<LIMBS> {
+        Uint::random_mod(rng, &self.order)
+    }
+
+    fn scalar_add(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        lhs.add_mod(rhs, &self.order)
+    }
+
+    fn scalar_mul(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        mul_mod_wide(lhs, rhs, &self.order)
+    }
+
+    fn scalar_sub(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+        lhs.sub_mod(rhs, &self.order)
+    }
+
+    fn scalar_invert(&self, scalar: &Uint<LIMBS>) -> Option<Uint<LIMBS>> {
+        // n is an odd prime for every curve worth using
+        let (inverse, invertible) = scalar.inv_odd_mod(&self.order);
+
+        bool::from(invertible).then_some(inverse)
+    }
+
+    fn scalar_from_u64(&self, value: u64) -> Uint<LIMBS> {
+        Uint::from_u64(value).rem(&self.order)
+    }
+
+    fn challenge_scalar(&self, transcript: &mut Transcript, label: &[u8]) -> Uint<LIMBS> {
+        transcript.challenge_uint(label, &self.order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::standard::{P256, SECP256K1};
+    use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    fn point(x: &str, y: &str) -> AffinePoint<{ U256::LIMBS }> {
+        AffinePoint::Coordinates {
+            x: U256::from_be_hex(x),
+            y: U256::from_be_hex(y),
+        }
+    }
+
+    #[test]
+    fn test_secp256k1_multiples() {
+        let curve = SECP256K1.curve().unwrap();
+        let g = curve.generator();
+
+        // multiples of G from the SEC2 / Bitcoin test vectors
+        let two = point(
+            "C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5",
+            "1AE168FEA63DC339A3C58419466CEAEEF7F632653266D0E1236431A950CFE52A",
+        );
+        let three = point(
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "388F7B0F632DE8140FE337E62A37F3566500A99934C2231B6CB9FD7584B8E672",
+        );
+
+        assert_eq!(curve.operate(&g, &g), two);
+        assert_eq!(curve.exp_generator(&U256::from_u64(2)), two);
+        assert_eq!(curve.exp_generator(&U256::from_u64(3)), three);
+        assert_eq!(curve.operate(&two, &g), three);
+    }
+
+    #[test]
+    fn test_p256_multiples() {
+        let curve = P256.curve().unwrap();
+
+        // multiples of G from the NIST point multiplication test vectors
+        let two = point(
+            "7CF27B188D034F7E8A52380304B51AC3C08969E277F21B35A60B48FC47669978",
+            "07775510DB8ED040293D9AC69F7430DBBA7DADE63CE982299E04B79D227873D1",
+        );
+        let three = point(
+            "5ECBE4D1A6330A44C8F7EF951D4BF165E6C6B721EFADA985FB41661BC6E7FD6C",
+            "8734640C4998FF7E374B06CE1A64A2ECD82AB036384FB83D9A79B127A27D5032",
+        );
+
+        assert_eq!(curve.exp_generator(&U256::from_u64(2)), two);
+        assert_eq!(curve.exp_generator(&U256::from_u64(3)), three);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        for curve in [SECP256K1.curve().unwrap(), P256.curve().unwrap()] {
+            let g = curve.generator();
+            let n_minus_1 = curve.order().wrapping_sub(&U256::ONE);
+
+            assert_eq!(curve.exp_generator(&curve.order()), curve.identity());
+            assert_eq!(curve.exp_generator(&n_minus_1), curve.invert(&g));
+            assert_eq!(curve.operate(&g, &curve.invert(&g)), curve.identity());
+            assert_eq!(curve.operate(&g, &curve.identity()), g);
+            assert_eq!(curve.exp(&curve.identity(), &n_minus_1), curve.identity());
+
+            let o = curve.identity();
+            assert_eq!(AffinePoint::conditional_select(&o, &g, Choice::from(1)), g);
+            assert_eq!(AffinePoint::conditional_select(&o, &g, Choice::from(0)), o);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        assert_eq!(SECP256K1.validate_with_rng(&mut rng), Ok(()));
+        assert_eq!(P256.validate_with_rng(&mut rng), Ok(()));
+
+        let mut check =
+            |parameters: CurveParameters<{ U256::LIMBS }>| parameters.validate_with_rng(&mut rng);
+        let off_curve = CurveParameters {
+            b: U256::from_u64(5),
+            ..SECP256K1
+        };
+        assert_eq!(check(off_curve), Err(ParameterError::GeneratorNotOnCurve));
+        let composite = CurveParameters {
+            order: SECP256K1.order.wrapping_add(&U256::ONE),
+            ..SECP256K1
+        };
+        assert_eq!(check(composite), Err(ParameterError::CompositeOrder));
+        let singular = CurveParameters {
+            b: U256::ZERO,
+            ..SECP256K1
+        };
+        assert_eq!(check(singular), Err(ParameterError::SingularCurve));
+        let wrong_order = CurveParameters {
+            order: P256.order,
+            ..SECP256K1
+        };
+        assert_eq!(check(wrong_order), Err(ParameterError::WrongGeneratorOrder));
+    }
+
+    #[test]
+    fn test_new() {
+        let new = |modulus| {
+            CurveParameters {
+                modulus,
+                ..SECP256K1
+            }
+            .curve()
+        };
+
+        assert_eq!(
+            new(U256::from_u64(45)),
+            Err(ParameterError::UnsupportedModulus)
+        );
+        assert_eq!(
+            new(U256::from_u64(46)),
+            Err(ParameterError::CompositeModulus)
+        );
+        assert_eq!(
+            new(U256::from_u64(47)),
+            Err(ParameterError::GeneratorOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_sec1_encoding() {
+        let curve = SECP256K1.curve().unwrap();
+        let g = curve.generator();
+
+        let bytes = curve.encode_element(&g);
+        assert_eq!(
+            hex(&bytes),
+            "0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"
+        );
+        assert_eq!(curve.decode_element(&bytes), Some(g));
+
+        // -G has the same x and odd y
+        let mut odd = bytes.clone();
+        odd[0] = TAG_ODD;
+        assert_eq!(curve.decode_element(&odd), Some(curve.invert(&g)));
+
+        let identity = curve.encode_element(&curve.identity());
+        assert_eq!(identity, vec![0; 33]);
+        assert_eq!(curve.decode_element(&identity), Some(curve.identity()));
+
+        let p256 = P256.curve().unwrap();
+        assert_eq!(
+            hex(&p256.encode_element(&p256.generator())),
+            "036B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296"
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects() {
+        let curve = SECP256K1.curve().unwrap();
+        let bytes = curve.encode_element(&curve.generator());
+
+        let mut uncompressed_tag = bytes.clone();
+        uncompressed_tag[0] = 0x04;
+        assert_eq!(curve.decode_element(&uncompressed_tag), None);
+        assert_eq!(curve.decode_element(&bytes[..32]), None);
+
+        // x = p is not reduced
+        let mut unreduced = vec![TAG_EVEN];
+        unreduced.extend(uint_to_bytes(&SECP256K1.modulus));
+        assert_eq!(curve.decode_element(&unreduced), None);
+
+        // x^3 + 7 is not a square for x = 5
+        let mut off_curve = vec![TAG_EVEN];
+        off_curve.extend(uint_to_bytes(&U256::from_u64(5)));
+        assert_eq!(curve.decode_element(&off_curve), None);
+
+        let mut identity = vec![0; 33];
+        identity[32] = 1;
+        assert_eq!(curve.decode_element(&identity), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let curve = P256.curve().unwrap();
+        let AffinePoint::Coordinates { x, y } = curve.generator() else {
+            unreachable!()
+        };
+
+        assert!(curve.contains(&curve.generator()));
+        assert!(curve.contains(&curve.identity()));
+        assert!(!curve.contains(&AffinePoint::Coordinates {
+            x,
+            y: y.wrapping_add(&U256::ONE)
+        }));
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+    }
+}
@@ -0,0 +1,295 @@
+//! The genuine interactive protocol behind [`prove`](crate::prove) / [`verify`](crate::verify).
+//!
+//! Each round goes commit -> challenge -> respond -> check:
+//!
+//! 1. prover picks random `r` and sends `h = g^r`
+//! 2. verifier replies with a random bit `b`
+//! 3. prover sends `s = r + b * x (mod order)`
+//! 4. verifier checks `g^s = h * y^b`
+//!
+//! A cheating prover survives each round with probability 1/2, so `n` rounds give soundness error 2^-n.
+//! The round states are consumed by each step, so a nonce `r` can never answer two challenges.
+
+use std::cell::Cell;
+
+use crypto_bigint::rand_core::{CryptoRngCore, OsRng};
+
+use crate::{Error, Group, PublicParams, Statement, Witness};
+
+/// Prover side, owning the secret x
+pub struct Prover<'a, G: Group> {
+    params: &'a PublicParams<G>,
+    witness: Witness<G>,
+    statement: Statement<G>,
+}
+
+/// Prover after sending commitment `h`, waiting for the challenge
+pub struct ProverRound<'a, G: Group> {
+    prover: &'a Prover<'a, G>,
+    r: G::Scalar,
+}
+
+/// Verifier side, knowing only the statement y = g^x
+pub struct Verifier<'a, G: Group> {
+    params: &'a PublicParams<G>,
+    statement: Statement<G>,
+    rounds: Cell<usize>, // challenges sent so far, numbering the rounds in errors
+}
+
+/// Verifier after sending challenge `b`, waiting for the response
+pub struct VerifierRound<'a, G: Group> {
+    verifier: &'a Verifier<'a, G>,
+    round: usize,
+    h: G::Element,
+    bit: bool,
+}
+
+impl<'a, G: Group> Prover<'a, G> {
+    pub fn new(params: &'a PublicParams<G>, witness: Witness<G>) -> Self {
+        let statement = Statement::from_witness(params, &witness);
+
+        Self {
+            params,
+            witness,
+            statement,
+        }
+    }
+
+    /// The statement y = g^x to hand to the verifier
+    pub fn statement(&self) -> Statement<G> {
+        self.statement
+    }
+
+    /// Step 1: pick random `r` and return commitment `h = g^r`
+    pub fn commit(&self) -> (ProverRound<'_, G>, G::Element) {
+        self.commit_with_rng(&mut OsRng)
+    }
+
+    /// Same as [`Self::commit`], drawing `r` from `rng`
+    pub fn commit_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> (ProverRound<'_, G>, G::Element) {
+        let group = self.params.group();
+        let r = group.random_scalar(rng);
+        let h = group.exp_generator(&r);
+
+        (ProverRound { prover: self, r }, h)
+    }
+}
+
+impl<G: Group> ProverRound<'_, G> {
+    /// Step 3: answer challenge bit `b` with `s = r + b * x (mod order)`
+    pub fn respond(self, bit: bool) -> G::Scalar {
+        if bit {
+            let Prover {
+                params, witness, ..
+            } = self.prover;
+
+            params.group().scalar_add(&self.r, witness.expose_secret())
+        } else {
+            self.r
+        }
+    }
+}
+
+impl<'a, G: Group> Verifier<'a, G> {
+    pub fn new(params: &'a PublicParams<G>, statement: Statement<G>) -> Self {
+        Self {
+            params,
+            statement,
+            rounds: Cell::new(0),
+        }
+    }
+
+    /// Step 2: receive commitment `h` and reply with a random challenge bit
+    pub fn challenge(&self, h: G::Element) -> (VerifierRound<'_, G>, bool) {
+        self.challenge_with_rng(h, &mut OsRng)
+    }
+
+    /// Same as [`Self::challenge`], drawing the bit from `rng`
+    pub fn challenge_with_rng(
+        &self,
+        h: G::Element,
+        rng: &mut impl CryptoRngCore,
+    ) -> (VerifierRound<'_, G>, bool) {
+        let bit = rng.next_u32() & 1 == 1;
+        let round = self.rounds.replace(self.rounds.get() + 1);
+
+        (
+            VerifierRound {
+                verifier: self,
+                round,
+                h,
+                bit,
+            },
+            bit,
+        )
+    }
+}
+
+impl<G: Group> VerifierRound<'_, G> {
+    /// Step 4: check `g^s = h * y^b`, errors carry the index of this challenge as the round
+    pub fn check(self, s: G::Scalar) -> Result<(), Error> {
+        let group = self.verifier.params.group();
+        let residue = self.verifier.statement.residue();
+        let round = self.round;
+        if !group.contains(&residue) {
+            return Err(Error::ResidueOutOfRange);
+        }
+        if !group.contains(&self.h) {
+            return Err(Error::NonCanonicalCommitment { round });
+        }
+        if !group.is_canonical_scalar(&s) {
+            return Err(Error::NonCanonicalResponse { round });
+        }
+
+        let lhs = group.exp_generator(&s); // g ^ s
+        let rhs = if self.bit {
+            group.operate(&self.h, &residue) // h * y
+        } else {
+            self.h
+        };
+
+        if lhs != rhs {
+            return Err(Error::RoundFailed { round });
+        }
+
+        Ok(())
+    }
+}
+
+/// Run `rounds` rounds of the protocol between `prover` and `verifier`, Ok if every round is accepted
+pub fn run_interactive<G: Group>(
+    prover: &Prover<'_, G>,
+    verifier: &Verifier<'_, G>,
+    rounds: usize,
+) -> Result<(), Error> {
+    run_interactive_with_rng(prover, verifier, rounds, &mut OsRng)
+}
+
+/// Same as [`run_interactive`], with both sides drawing their randomness from `rng`
+pub fn run_interactive_with_rng<G: Group>(
+    prover: &Prover<'_, G>,
+    verifier: &Verifier<'_, G>,
+    rounds: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(), Error> {
+    (0..rounds).try_for_each(|_| {
+        let (prover_round, h) = prover.commit_with_rng(rng);
+        let (verifier_round, bit) = verifier.challenge_with_rng(h, rng);
+        let s = prover_round.respond(bit);
+
+        verifier_round.check(s)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::params;
+    use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn test_positive() {
+        let params = params(47, 23, 2);
+        let prover = Prover::new(&params, Witness::new(&params, U256::from_u64(17)));
+        let verifier = Verifier::new(&params, prover.statement());
+
+        assert_eq!(run_interactive(&prover, &verifier, 100), Ok(()));
+    }
+
+    #[test]
+    fn test_negative() {
+        let params = params(59, 29, 4);
+        let prover = Prover::new(&params, Witness::new(&params, U256::from_u64(10)));
+        let verifier = Verifier::new(
+            &params,
+            Statement::new(prover.statement().residue().wrapping_add(&U256::ONE)),
+        );
+
+        // 28 + 1 is in the group as well, only a round with b = 1 can fail
+        assert!(matches!(
+            run_interactive(&prover, &verifier, 100),
+            Err(Error::RoundFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reject_out_of_range() {
+        let params = params(59, 29, 4);
+        let prover = Prover::new(&params, Witness::new(&params, U256::from_u64(10)));
+        let verifier = Verifier::new(&params, prover.statement());
+
+        // g^s and g^(s + q) are equal, only the reduced s is accepted
+        let (prover_round, h) = prover.commit();
+        let (verifier_round, bit) = verifier.challenge(h);
+        let s = prover_round.respond(bit).wrapping_add(&U256::from_u64(29));
+        assert_eq!(
+            verifier_round.check(s),
+            Err(Error::NonCanonicalResponse { round: 0 })
+        );
+
+        let (verifier_round, _) = verifier.challenge(U256::ZERO);
+        assert_eq!(
+            verifier_round.check(U256::ZERO),
+            Err(Error::NonCanonicalCommitment { round: 1 })
+        );
+
+        let verifier = Verifier::new(&params, Statement::new(U256::from_u64(2)));
+        assert_eq!(
+            run_interactive(&prover, &verifier, 1),
+            Err(Error::ResidueOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_unreduced_witness() {
+        // 39 = 10 (mod 29), the prover answers for x = 10
+        let params = params(59, 29, 4);
+        for secret in [U256::from_u64(39), U256::MAX] {
+            let prover = Prover::new(&params, Witness::new(&params, secret));
+            let verifier = Verifier::new(&params, prover.statement());
+
+            assert_eq!(run_interactive(&prover, &verifier, 100), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_step_by_step() {
+        let params = params(59, 29, 4);
+        let prover = Prover::new(&params, Witness::new(&params, U256::from_u64(10)));
+        let verifier = Verifier::new(&params, prover.statement());
+
+        let (prover_round, h) = prover.commit();
+        let (verifier_round, bit) = verifier.challenge(h);
+        let s = prover_round.respond(bit);
+
+        assert_eq!(verifier_round.check(s), Ok(()));
+    }
+
+    #[test]
+    fn test_seeded_rng() {
+        let params = params(59, 29, 4);
+        let prover = Prover::new(&params, Witness::new(&params, U256::from_u64(10)));
+        let verifier = Verifier::new(&params, prover.statement());
+
+        let run = |seed| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let (prover_round, h) = prover.commit_with_rng(&mut rng);
+            let (verifier_round, bit) = verifier.challenge_with_rng(h, &mut rng);
+            let s = prover_round.respond(bit);
+
+            assert_eq!(verifier_round.check(s), Ok(()));
+            (h, bit, s)
+        };
+        assert_eq!(run(1), run(1));
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2);
+        assert_eq!(
+            run_interactive_with_rng(&prover, &verifier, 100, &mut rng),
+            Ok(())
+        );
+    }
+}
@@ -6,169 +6,693 @@
 //! - a function verify(y, g, p, pf) that evaluates to true if pf is a valid proof of knowledge, and false otherwise.
 //!   The prover should only be able to compute a valid proof with non-negligible probability
 //!   if they do indeed know valid x.
+//!
+//! The proofs are written over any [`Group`], (g, p) above is the [`ModpGroup`] of parameters (p, q, g)
+//! where g has prime order q.
 
 use crypto_bigint::{
-    modular::runtime_mod::{DynResidue, DynResidueParams},
-    rand_core::OsRng,
-    NonZero, RandomMod, Uint,
+    rand_core::{CryptoRngCore, OsRng},
+    NonZero, Uint,
 };
 
-const ROUND_OF_VERIFY: usize = 100;
+mod and_proof;
+mod dleq;
+pub mod encoding;
+mod error;
+pub mod extractor;
+pub mod forensics;
+pub mod group;
+pub mod interactive;
+mod or_proof;
+pub mod paramgen;
+pub mod primality;
+mod schnorr;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod simulator;
+mod statement;
+#[cfg(test)]
+mod test_util;
+pub mod transcript;
 
-pub struct Proof<const LIMBS: usize> {
-    pub h: Uint<LIMBS>, // h = g^r (mod p)
-    pub s: Uint<LIMBS>, // s = (r + b * x) (mod q - 1)
+pub use and_proof::{
+    prove_and, prove_and_with_context, prove_and_with_rng, verify_and, verify_and_with_context,
+    AndProof,
+};
+pub use dleq::{
+    prove_dleq, prove_dleq_with_context, prove_dleq_with_rng, verify_dleq,
+    verify_dleq_with_context, DleqProof, DleqStatement,
+};
+pub use encoding::Encoding;
+pub use error::{Error, ExtractionError, ParameterError};
+pub use group::{
+    validate_parameters, validate_parameters_with_rng, CurveParameters, Group, ModpGroup,
+    ModpParameters, Ristretto255, WeierstrassCurve,
+};
+pub use interactive::{run_interactive, run_interactive_with_rng, Prover, Verifier};
+pub use or_proof::{
+    prove_or, prove_or_with_context, prove_or_with_rng, verify_or, verify_or_with_context,
+    OrBranch, OrProof,
+};
+pub use paramgen::generate_parameters;
+pub use schnorr::{
+    prove_schnorr, prove_schnorr_with_context, prove_schnorr_with_rng, verify_schnorr,
+    verify_schnorr_with_context, SchnorrProof,
+};
+pub use statement::{PublicParams, Statement, Witness};
+pub use transcript::Transcript;
+
+/// Rounds of [`prove`] and minimum rounds of [`verify`], a soundness error of 2^-100
+pub const ROUND_OF_VERIFY: usize = 100;
+
+// protocol label absorbed first into the transcript of the binary-challenge proof
+const PROTOCOL_LABEL: &[u8] = b"dlog-binary-rounds";
+
+// protocol label of the transcript deterministic nonces are squeezed from
+const NONCE_LABEL: &[u8] = b"dlog-binary-rounds-nonces";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        bound = "G::Element: crate::encoding::FixedBytes, G::Scalar: crate::encoding::FixedBytes"
+    )
+)]
+pub struct Proof<G: Group> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub h: G::Element, // h = g^r
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub s: G::Scalar, // s = (r + b * x) (mod order)
 }
 
-pub type Proofs<const LIMBS: usize> = Vec<Proof<LIMBS>>;
+/// One [`Proof`] per round, the number of rounds is the length
+pub type Proofs<G> = Vec<Proof<G>>;
 
-/// dlogProof(x, g, p) to prove that we know secret x such that y = g^x (mod p)
+/// Rounds needed so that a prover not knowing x is accepted with probability at most 2^-`bits`
 ///
-/// Returns (residue y, proofs)
-pub fn prove<const LIMBS: usize>(
-    secret: Uint<LIMBS>,
-    generator: Uint<LIMBS>,
-    modulus: Uint<LIMBS>,
-) -> (Uint<LIMBS>, Proofs<LIMBS>) {
-    assert!(modulus > <Uint<LIMBS>>::ONE); // assert p > 1
-
-    // y = g^x (mod p)
-    let rem_cac_cache = RemCaculateCache::new(&modulus); // store p in cache
-    let residue = rem_cac_cache.pow_mod(&generator, &secret);
-
-    // caculate p - 1 to generate `r` after
-    let p_minus_1 = modulus.wrapping_sub(&<Uint<LIMBS>>::ONE);
-    // we asserted p > 1 before, so it is safe to unwrap here
-    let non_zero_modulus = NonZero::new(p_minus_1).unwrap();
+/// A cheating prover can answer exactly one of the two challenges of a round,
+/// so every round halves its chance and the answer is `bits` itself.
+pub const fn rounds_for_soundness(bits: usize) -> usize {
+    bits
+}
 
-    // caculate proofs
-    let mut proofs = Vec::with_capacity(ROUND_OF_VERIFY);
-    for _ in 0..ROUND_OF_VERIFY {
-        // generate random `r` below (p-1) and compute each `h = g^r (mod p)`
-        let r = Uint::<LIMBS>::random_mod(&mut OsRng, &non_zero_modulus);
-        let h = rem_cac_cache.pow_mod(&generator, &r);
-        let bit = get_bit_by_hashing(&h, &generator, &modulus);
-
-        let s = if bit > 0 {
-            r.add_mod(&secret, &non_zero_modulus)
-        } else {
-            r
-        };
+/// dlogProof(x, g, p) to prove that we know secret x such that y = g^x
+///
+/// Returns (statement y, proofs)
+pub fn prove<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+) -> (Statement<G>, Proofs<G>) {
+    prove_with_context(params, witness, &[])
+}
 
-        proofs.push(Proof { h, s });
-    }
+/// Same as [`prove`], with the proof bound to a caller chosen `context` (session id, message, ...)
+///
+/// The proof only verifies under the same `context`
+pub fn prove_with_context<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    context: &[u8],
+) -> (Statement<G>, Proofs<G>) {
+    prove_with_rounds(params, witness, ROUND_OF_VERIFY, context)
+}
+
+/// Same as [`prove_with_context`], running `rounds` rounds instead of [`ROUND_OF_VERIFY`]
+///
+/// See [`rounds_for_soundness`] to pick `rounds` from a soundness level.
+pub fn prove_with_rounds<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    rounds: usize,
+    context: &[u8],
+) -> (Statement<G>, Proofs<G>) {
+    prove_with_rng(params, witness, rounds, context, &mut OsRng)
+}
+
+/// Same as [`prove_with_rounds`], drawing the nonces from `rng` instead of the OS
+///
+/// A seeded `rng` gives reproducible proofs for known-answer tests, it must never be reused
+/// for proofs of the same secret.
+pub fn prove_with_rng<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    rounds: usize,
+    context: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> (Statement<G>, Proofs<G>) {
+    let group = params.group();
+
+    // generate random `r` for every round
+    let nonces = (0..rounds).map(|_| group.random_scalar(rng)).collect();
+
+    let (residue, proofs) = prove_with_nonces(group, witness.expose_secret(), nonces, context);
+    (Statement::new(residue), proofs)
+}
+
+/// Same as [`prove_with_context`], with nonces derived from the secret instead of drawn at random
+///
+/// Like RFC 6979, each `r` is hashed from x, the statement, the context and the round number,
+/// so the same inputs always give the same proof and a broken RNG cannot leak x.
+pub fn prove_deterministic<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    context: &[u8],
+) -> (Statement<G>, Proofs<G>) {
+    prove_deterministic_with_rounds(params, witness, ROUND_OF_VERIFY, context, &[])
+}
+
+/// Same as [`prove_deterministic`], running `rounds` rounds and hedging with `extra_entropy`
+///
+/// `extra_entropy` (e.g. fresh random bytes) is hashed into every nonce next to the secret:
+/// good entropy makes proofs unlinkable again, bad entropy is no worse than none at all.
+pub fn prove_deterministic_with_rounds<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    rounds: usize,
+    context: &[u8],
+    extra_entropy: &[u8],
+) -> (Statement<G>, Proofs<G>) {
+    let group = params.group();
+    let secret = witness.expose_secret();
+    let residue = group.exp_generator(secret);
+
+    // everything the challenge bits depend on goes in, so one nonce never meets two different bits
+    let mut transcript = new_transcript(NONCE_LABEL, group, &residue, context);
+    transcript.append_message(b"rounds", &(rounds as u64).to_be_bytes());
+    transcript.append_message(b"x", &group.encode_scalar(secret));
+    transcript.append_message(b"entropy", extra_entropy);
+
+    let nonces = (0..rounds)
+        .map(|round| {
+            transcript.append_message(b"round", &(round as u64).to_be_bytes());
+            group.challenge_scalar(&mut transcript, b"r")
+        })
+        .collect();
+
+    let (residue, proofs) = prove_with_nonces(group, secret, nonces, context);
+    (Statement::new(residue), proofs)
+}
+
+/// Run the rounds with the given nonces, one per round
+fn prove_with_nonces<G: Group>(
+    group: &G,
+    secret: &G::Scalar,
+    nonces: Vec<G::Scalar>,
+    context: &[u8],
+) -> (G::Element, Proofs<G>) {
+    // y = g^x
+    let residue = group.exp_generator(secret);
+
+    // commit to each `h = g^r` before any challenge exists
+    let commitments: Vec<_> = nonces.iter().map(|r| group.exp_generator(r)).collect();
+
+    // caculate proofs
+    let bits = get_bits_by_hashing(group, &residue, context, &commitments);
+    let proofs = nonces
+        .into_iter()
+        .zip(commitments)
+        .zip(bits)
+        .map(|((r, h), bit)| {
+            let s = if bit { group.scalar_add(&r, secret) } else { r };
+
+            Proof { h, s }
+        })
+        .collect();
 
     (residue, proofs)
 }
 
-/// a function verify(y, g, p, pf) that evaluates to true if pf is a valid proof of knowledge, and false otherwise.
+/// a function verify(y, g, p, pf) that evaluates to Ok if pf is a valid proof of knowledge, and why not otherwise.
 /// The prover should only be able to compute a valid proof with non-negligible probability
 /// if they do indeed know valid x.
-pub fn verify<const LIMBS: usize>(
-    residue: Uint<LIMBS>,
-    generator: Uint<LIMBS>,
-    modulus: Uint<LIMBS>,
-    proofs: Proofs<LIMBS>,
-) -> bool {
-    if proofs.len() != ROUND_OF_VERIFY {
-        return false;
-    }
+pub fn verify<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    proofs: Proofs<G>,
+) -> Result<(), Error> {
+    verify_with_context(params, statement, &[], proofs)
+}
 
-    let rem_cac_cache = RemCaculateCache::new(&modulus);
+/// Same as [`verify`], for proofs made by [`prove_with_context`]
+pub fn verify_with_context<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    context: &[u8],
+    proofs: Proofs<G>,
+) -> Result<(), Error> {
+    verify_with_min_rounds(params, statement, ROUND_OF_VERIFY, context, proofs)
+}
 
-    proofs.iter().all(|proof| {
-        let Proof { h, s } = proof;
-        let bit = get_bit_by_hashing(&h, &generator, &modulus);
+/// Same as [`verify_with_context`], accepting proofs of at least `min_rounds` rounds
+///
+/// Proofs made by [`prove_with_rounds`] with more rounds than required are accepted too.
+/// A proof without any round shows nothing, at least one is required even for `min_rounds = 0`.
+pub fn verify_with_min_rounds<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    min_rounds: usize,
+    context: &[u8],
+    proofs: Proofs<G>,
+) -> Result<(), Error> {
+    let min_rounds = min_rounds.max(1);
+    if proofs.len() < min_rounds {
+        return Err(Error::TooFewRounds {
+            minimum: min_rounds,
+            found: proofs.len(),
+        });
+    }
 
-        let lhs = rem_cac_cache.pow_mod(&generator, &s); // g ^ s (mod p)
-        let rhs = if bit > 0 {
-            rem_cac_cache.mul_mod(&h, &residue) // h * y (mod p)
-        } else {
-            *h
-        };
+    let group = params.group();
+    let residue = statement.residue();
+    check_elements(group, &residue, &proofs)?;
 
-        // println!("h: {:?}, s: {:?}, bit: {}", h, s, bit);
-        // println!("lhs: {:?}, rhs: {:?}", lhs, rhs);
+    let commitments: Vec<_> = proofs.iter().map(|proof| proof.h).collect();
+    let bits = get_bits_by_hashing(group, &residue, context, &commitments);
 
-        lhs == rhs
-    })
+    check_rounds(group, &residue, &proofs, &bits)
 }
 
-fn get_bit_by_hashing<const LIMBS: usize>(
-    h: &Uint<LIMBS>,
-    generator: &Uint<LIMBS>,
-    modulus: &Uint<LIMBS>,
-) -> u8 {
-    let mut hasher = blake3::Hasher::new();
-    hasher.update(transmute_uint_to_u8_slice(h));
-    hasher.update(transmute_uint_to_u8_slice(generator));
-    hasher.update(transmute_uint_to_u8_slice(modulus));
-    let hash = hasher.finalize();
-    hash.as_bytes()[0] % 2
-}
-
-fn transmute_uint_to_u8_slice<const LIMBS: usize>(v: &Uint<LIMBS>) -> &[u8] {
-    let slice = unsafe { v.as_words().align_to::<u8>().1 };
-    assert_eq!(slice.len(), LIMBS * 8);
+/// y and every h must lie in the group, otherwise `g^s = h * y^b` says nothing about x
+///
+/// Every s must be reduced as well, or `s + order` would be a second valid proof.
+fn check_elements<G: Group>(
+    group: &G,
+    residue: &G::Element,
+    proofs: &[Proof<G>],
+) -> Result<(), Error> {
+    if !group.contains(residue) {
+        return Err(Error::ResidueOutOfRange);
+    }
+    if let Some(round) = proofs.iter().position(|proof| !group.contains(&proof.h)) {
+        return Err(Error::NonCanonicalCommitment { round });
+    }
+    if let Some(round) = proofs
+        .iter()
+        .position(|proof| !group.is_canonical_scalar(&proof.s))
+    {
+        return Err(Error::NonCanonicalResponse { round });
+    }
 
-    slice
+    Ok(())
 }
 
-/// Wraps around a weird API from crypto-bigint to compute exponentiation with remainder
+/// Every base of a statement must be a non-identity element of the group
 ///
-/// Precompute some stuff (?) for montgomery reduction and reuse it in each computation with same modulus
-struct RemCaculateCache<const LIMBS: usize> {
-    dyn_residue_params: DynResidueParams<LIMBS>,
+/// With the identity as base the residue is the identity whatever x is, and says nothing about it.
+fn check_bases<G: Group>(group: &G, bases: &[G::Element]) -> Result<(), Error> {
+    if !bases
+        .iter()
+        .all(|base| group.contains(base) && *base != group.identity())
+    {
+        return Err(Error::BaseOutOfRange);
+    }
+
+    Ok(())
 }
 
-impl<const LIMBS: usize> RemCaculateCache<LIMBS> {
-    fn new(modulus: &Uint<LIMBS>) -> Self {
-        let dyn_residue_params = DynResidueParams::new(&modulus);
+/// Check `g^s = h * y^b` of every round against its challenge bit
+fn check_rounds<G: Group>(
+    group: &G,
+    residue: &G::Element,
+    proofs: &[Proof<G>],
+    bits: &[bool],
+) -> Result<(), Error> {
+    let failed_round = proofs.iter().zip(bits).position(|(proof, bit)| {
+        let Proof { h, s } = proof;
 
-        Self { dyn_residue_params }
-    }
+        let lhs = group.exp_generator(s); // g ^ s
+        let rhs = if *bit {
+            group.operate(h, residue) // h * y
+        } else {
+            *h
+        };
 
-    fn pow_mod(&self, base: &Uint<LIMBS>, exp: &Uint<LIMBS>) -> Uint<LIMBS> {
-        let dyn_residue = DynResidue::new(base, self.dyn_residue_params);
+        lhs != rhs
+    });
 
-        dyn_residue.pow(exp).retrieve()
+    match failed_round {
+        Some(round) => Err(Error::RoundFailed { round }),
+        None => Ok(()),
     }
+}
 
-    fn mul_mod(&self, lhs: &Uint<LIMBS>, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
-        let dyn_residue_lhs = DynResidue::new(lhs, self.dyn_residue_params);
-        let dyn_residue_rhs = DynResidue::new(rhs, self.dyn_residue_params);
+/// Start a transcript bound to the group, the statement y = g^x and the caller's context
+fn new_transcript<G: Group>(
+    protocol_label: &[u8],
+    group: &G,
+    residue: &G::Element,
+    context: &[u8],
+) -> Transcript {
+    let mut transcript = Transcript::new(protocol_label);
+    transcript.append_message(b"group", &group.encode_parameters());
+    transcript.append_element(b"g", group, &group.generator());
+    transcript.append_element(b"y", group, residue);
+    transcript.append_message(b"context", context);
+
+    transcript
+}
 
-        dyn_residue_lhs.mul(&dyn_residue_rhs).retrieve()
+/// Derive the challenge bit of every round from one transcript over the statement and all commitments
+fn get_bits_by_hashing<G: Group>(
+    group: &G,
+    residue: &G::Element,
+    context: &[u8],
+    commitments: &[G::Element],
+) -> Vec<bool> {
+    let mut transcript = new_transcript(PROTOCOL_LABEL, group, residue, context);
+    for h in commitments {
+        transcript.append_element(b"h", group, h);
     }
+
+    transcript.challenge_bits(b"b", commitments.len())
+}
+
+/// (lhs * rhs) mod modulus, for arithmetic on exponents
+fn mul_mod_wide<const LIMBS: usize>(
+    lhs: &Uint<LIMBS>,
+    rhs: &Uint<LIMBS>,
+    modulus: &NonZero<Uint<LIMBS>>,
+) -> Uint<LIMBS> {
+    Uint::const_rem_wide(lhs.mul_wide(rhs), modulus).0
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::params;
     use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 
     #[test]
     fn test_positive() {
-        let secret = U256::from_u64(17);
-        let generator = U256::from_u64(3);
-        let modulus = U256::from_u64(31);
-        let (residue, proofs) = prove(secret, generator, modulus);
+        let params = params(47, 23, 2);
+        let witness = Witness::new(&params, U256::from_u64(17));
+        let (statement, proofs) = prove(&params, &witness);
 
-        assert!(verify(residue, generator, modulus, proofs));
+        assert_eq!(verify(&params, &statement, proofs), Ok(()));
     }
 
     #[test]
     fn test_negative() {
-        let secret = U256::from_u64(10);
-        let generator = U256::from_u64(2);
-        let modulus = U256::from_u64(67);
-        let (residue, proofs) = prove(secret, generator, modulus);
-
-        assert!(!verify(
-            residue.wrapping_add(&U256::ONE),
-            generator,
-            modulus,
-            proofs
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, proofs) = prove(&params, &witness);
+
+        assert!(matches!(
+            verify(
+                &params,
+                &Statement::new(statement.residue().wrapping_add(&U256::ONE)),
+                proofs
+            ),
+            Err(Error::RoundFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_residue_outside_subgroup() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, proofs) = prove(&params, &witness);
+
+        // -y has order 2q, it is not in the group generated by g
+        let minus_one = U256::from_u64(58);
+        assert_eq!(
+            verify(
+                &params,
+                &Statement::new(params.group().operate(&statement.residue(), &minus_one)),
+                proofs
+            ),
+            Err(Error::ResidueOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_curves() {
+        for parameters in [group::standard::SECP256K1, group::standard::P256] {
+            let params = PublicParams::new_unchecked(parameters.curve().unwrap());
+            let witness = Witness::random(&params, &mut ChaCha20Rng::seed_from_u64(1));
+            let (statement, proofs) = prove(&params, &witness);
+
+            assert_eq!(proofs.to_bytes(params.group()).len(), 100 * (33 + 32));
+            assert_eq!(verify(&params, &statement, proofs.clone()), Ok(()));
+
+            let (schnorr_statement, proof) = prove_schnorr(&params, &witness);
+            assert_eq!(schnorr_statement, statement);
+            assert_eq!(verify_schnorr(&params, &statement, proof), Ok(()));
+
+            let other = Statement::new(params.group().generator());
+            assert!(matches!(
+                verify(&params, &other, proofs),
+                Err(Error::RoundFailed { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ristretto255() {
+        let params = PublicParams::new(Ristretto255::new()).unwrap();
+        let witness = Witness::random(&params, &mut ChaCha20Rng::seed_from_u64(1));
+        let (statement, proofs) = prove(&params, &witness);
+
+        assert_eq!(proofs.to_bytes(params.group()).len(), 100 * (32 + 32));
+        assert_eq!(verify(&params, &statement, proofs.clone()), Ok(()));
+
+        let (_, proof) = prove_schnorr(&params, &witness);
+        assert_eq!(verify_schnorr(&params, &statement, proof), Ok(()));
+
+        let other = Statement::new(params.group().generator());
+        assert!(matches!(
+            verify(&params, &other, proofs),
+            Err(Error::RoundFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_wrong_context() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, proofs) = prove_with_context(&params, &witness, b"alice");
+
+        assert!(matches!(
+            verify_with_context(&params, &statement, b"bob", proofs),
+            Err(Error::RoundFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_wrong_round_count() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, mut proofs) = prove(&params, &witness);
+        proofs.pop();
+
+        assert_eq!(
+            verify(&params, &statement, proofs),
+            Err(Error::TooFewRounds {
+                minimum: 100,
+                found: 99
+            })
+        );
+    }
+
+    #[test]
+    fn test_rounds() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let rounds = rounds_for_soundness(128);
+        let (statement, proofs) = prove_with_rounds(&params, &witness, rounds, b"");
+
+        assert_eq!(proofs.len(), 128);
+        assert_eq!(verify(&params, &statement, proofs.clone()), Ok(()));
+        assert_eq!(
+            verify_with_min_rounds(&params, &statement, 128, b"", proofs.clone()),
+            Ok(())
+        );
+        assert_eq!(
+            verify_with_min_rounds(&params, &statement, 129, b"", proofs),
+            Err(Error::TooFewRounds {
+                minimum: 129,
+                found: 128
+            })
+        );
+
+        // a short proof is fine for a verifier asking for little
+        let (statement, proofs) = prove_with_rounds(&params, &witness, 16, b"");
+        assert_eq!(
+            verify_with_min_rounds(&params, &statement, 16, b"", proofs.clone()),
+            Ok(())
+        );
+        assert!(verify(&params, &statement, proofs).is_err());
+
+        // but never an empty one
+        assert_eq!(
+            verify_with_min_rounds(&params, &statement, 0, b"", vec![]),
+            Err(Error::TooFewRounds {
+                minimum: 1,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncated_proof() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, mut proofs) = prove_with_rounds(&params, &witness, 32, b"");
+        proofs.truncate(16);
+
+        // the challenges depend on every commitment, dropping rounds changes them
+        assert!(matches!(
+            verify_with_min_rounds(&params, &statement, 16, b"", proofs),
+            Err(Error::RoundFailed { .. })
         ));
     }
+
+    #[test]
+    fn test_seeded_rng() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let prove_seeded = |seed| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            prove_with_rng(&params, &witness, ROUND_OF_VERIFY, b"", &mut rng)
+        };
+
+        let (statement, proofs) = prove_seeded(1);
+        assert_eq!(prove_seeded(1), (statement, proofs.clone()));
+        assert_ne!(prove_seeded(2).1, proofs);
+        assert_eq!(verify(&params, &statement, proofs), Ok(()));
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+
+        let (statement, proofs) = prove_deterministic(&params, &witness, b"alice");
+        assert_eq!(
+            prove_deterministic(&params, &witness, b"alice"),
+            (statement, proofs.clone())
+        );
+        assert_eq!(
+            verify_with_context(&params, &statement, b"alice", proofs.clone()),
+            Ok(())
+        );
+
+        // anything changing the challenges changes the nonces too
+        let (_, other) = prove_deterministic(&params, &witness, b"bob");
+        assert_ne!(other, proofs);
+        let (_, shorter) = prove_deterministic_with_rounds(&params, &witness, 99, b"alice", &[]);
+        assert_ne!(shorter[..], proofs[..99]);
+
+        let (_, hedged) =
+            prove_deterministic_with_rounds(&params, &witness, 100, b"alice", b"seed");
+        assert_ne!(hedged, proofs);
+        assert_eq!(
+            verify_with_context(&params, &statement, b"alice", hedged),
+            Ok(())
+        );
+    }
+
+    // a prover without x, answering only challenge 0, resampling a round's `r` until its bit is 0
+    fn grinding_forger<G: Group>(
+        group: &G,
+        challenge_bits: impl Fn(&[G::Element]) -> Vec<bool>,
+    ) -> Proofs<G> {
+        let mut nonces: Vec<_> = (0..ROUND_OF_VERIFY)
+            .map(|_| group.random_scalar(&mut OsRng))
+            .collect();
+        let mut commitments: Vec<_> = nonces.iter().map(|r| group.exp_generator(r)).collect();
+
+        for round in 0..ROUND_OF_VERIFY {
+            for _ in 0..64 {
+                if !challenge_bits(&commitments)[round] {
+                    break;
+                }
+                nonces[round] = group.random_scalar(&mut OsRng);
+                commitments[round] = group.exp_generator(&nonces[round]);
+            }
+        }
+
+        nonces
+            .into_iter()
+            .zip(commitments)
+            .map(|(s, h)| Proof { h, s })
+            .collect()
+    }
+
+    #[test]
+    fn test_grinding_forger() {
+        let params = params(59, 29, 4);
+        let group = params.group();
+        let residue = group.exp_generator(&U256::from_u64(10));
+
+        // bits hashed from each round's h alone fall one by one to grinding
+        let per_round_bits = |commitments: &[U256]| -> Vec<bool> {
+            commitments
+                .iter()
+                .map(|h| get_bits_by_hashing(group, &residue, b"", &[*h])[0])
+                .collect()
+        };
+        let proofs = grinding_forger(group, per_round_bits);
+        assert!(
+            per_round_bits(&proofs.iter().map(|proof| proof.h).collect::<Vec<_>>())
+                .iter()
+                .all(|bit| !bit)
+        );
+
+        // with every bit hashed over all commitments, resampling one round redraws all of them
+        let proofs = grinding_forger(group, |commitments| {
+            get_bits_by_hashing(group, &residue, b"", commitments)
+        });
+        assert!(matches!(
+            verify(&params, &Statement::new(residue), proofs),
+            Err(Error::RoundFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_non_canonical_commitment() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, mut proofs) = prove(&params, &witness);
+        proofs[42].h = proofs[42].h.wrapping_add(&U256::from_u64(59)); // same residue, not reduced
+
+        assert_eq!(
+            verify(&params, &statement, proofs),
+            Err(Error::NonCanonicalCommitment { round: 42 })
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_response() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, mut proofs) = prove(&params, &witness);
+        proofs[42].s = proofs[42].s.wrapping_add(&U256::from_u64(29)); // same exponent, not reduced
+
+        assert_eq!(
+            verify(&params, &statement, proofs),
+            Err(Error::NonCanonicalResponse { round: 42 })
+        );
+    }
+    #[test]
+    fn test_unreduced_witness() {
+        // 39 = 10 (mod 29), both prove the same statement
+        let params = params(59, 29, 4);
+        let expected = Statement::from_witness(&params, &Witness::new(&params, U256::from_u64(10)));
+
+        for secret in [U256::from_u64(39), U256::MAX] {
+            let witness = Witness::new(&params, secret);
+            let (statement, proofs) = prove(&params, &witness);
+            assert_eq!(verify(&params, &statement, proofs), Ok(()));
+
+            let (statement, proofs) = prove_deterministic(&params, &witness, b"");
+            assert_eq!(verify(&params, &statement, proofs), Ok(()));
+        }
+        assert_eq!(
+            prove(&params, &Witness::new(&params, U256::from_u64(39))).0,
+            expected
+        );
+    }
 }
@@ -0,0 +1,375 @@
+//! Cramer–Damgård–Schoenmakers OR-proof: knowledge of the discrete log of one of `y_1..y_n`.
+//!
+//! Every residue gets a Schnorr branch `(h_j, c_j, s_j)` with `g^s_j = h_j * y_j^c_j`, and the
+//! branch challenges must add up to the challenge `c` hashed from all commitments. The prover
+//! simulates every branch but the one it knows x for, picking `c_j` and `s_j` first and solving
+//! for `h_j`. The real branch gets whatever is left of `c`, so all branches look alike and the
+//! proof does not reveal which residue the prover owns (e.g. which member of a group posted).
+//! Nor does the prover's running time: it does the work of a simulated branch for all of them.
+
+use crypto_bigint::{
+    rand_core::{CryptoRngCore, OsRng},
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq},
+};
+
+use crate::{Error, Group, PublicParams, Statement, Transcript, Witness};
+
+// protocol label absorbed first into the transcript of the or-proof
+const PROTOCOL_LABEL: &[u8] = b"dlog-cds-or";
+
+/// One branch of an [`OrProof`], an accepting Schnorr transcript for one residue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        bound = "G::Element: crate::encoding::FixedBytes, G::Scalar: crate::encoding::FixedBytes"
+    )
+)]
+pub struct OrBranch<G: Group> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub h: G::Element, // h = g^r, or g^s * y^-c when simulated
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub c: G::Scalar, // share of the challenge
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub s: G::Scalar, // s = (r + c * x) (mod order)
+}
+
+/// One [`OrBranch`] per residue, in the order of the statements
+pub type OrProof<G> = Vec<OrBranch<G>>;
+
+/// Prove that we know the discrete log of one of `statements`, without revealing which
+///
+/// `witness` is the secret of `statements[index]`, [`Error::WrongWitness`] is returned if it
+/// is not or if there is no such statement.
+pub fn prove_or<G: Group>(
+    params: &PublicParams<G>,
+    statements: &[Statement<G>],
+    index: usize,
+    witness: &Witness<G>,
+) -> Result<OrProof<G>, Error> {
+    prove_or_with_context(params, statements, index, witness, &[])
+}
+
+/// Same as [`prove_or`], with the proof bound to a caller chosen `context`
+pub fn prove_or_with_context<G: Group>(
+    params: &PublicParams<G>,
+    statements: &[Statement<G>],
+    index: usize,
+    witness: &Witness<G>,
+    context: &[u8],
+) -> Result<OrProof<G>, Error> {
+    prove_or_with_rng(params, statements, index, witness, context, &mut OsRng)
+}
+
+/// Same as [`prove_or_with_context`], drawing the nonce and the simulated branches from `rng`
+pub fn prove_or_with_rng<G: Group>(
+    params: &PublicParams<G>,
+    statements: &[Statement<G>],
+    index: usize,
+    witness: &Witness<G>,
+    context: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<OrProof<G>, Error> {
+    if statements.get(index) != Some(&Statement::from_witness(params, witness)) {
+        return Err(Error::WrongWitness);
+    }
+
+    let group = params.group();
+    let r = group.random_scalar(rng);
+    let h_real = group.exp_generator(&r); // h = g^r
+
+    // every branch is simulated, the real one is swapped in by selects afterwards,
+    // so neither the work nor the time it takes depends on `index`
+    let is_real = |j: usize| -> Choice { j.ct_eq(&index) };
+    let simulated: Vec<_> = statements
+        .iter()
+        .map(|statement| {
+            let (c, s) = (group.random_scalar(rng), group.random_scalar(rng));
+            // h = g^s * y^-c
+            let h = group.operate(
+                &group.exp_generator(&s),
+                &group.invert(&group.exp(&statement.residue(), &c)),
+            );
+
+            (h, c, s)
+        })
+        .collect();
+    let commitments: Vec<_> = simulated
+        .iter()
+        .enumerate()
+        .map(|(j, (h, _, _))| G::Element::conditional_select(h, &h_real, is_real(j)))
+        .collect();
+
+    // the real branch answers what is left of c
+    let c = get_challenge_by_hashing(group, statements, context, &commitments);
+    let zero = group.scalar_from_u64(0);
+    let c_real = simulated
+        .iter()
+        .enumerate()
+        .fold(c, |rest, (j, (_, c, _))| {
+            group.scalar_sub(&rest, &G::Scalar::conditional_select(c, &zero, is_real(j)))
+        });
+    let s_real = group.scalar_add(&r, &group.scalar_mul(&c_real, witness.expose_secret()));
+
+    Ok(commitments
+        .into_iter()
+        .zip(simulated)
+        .enumerate()
+        .map(|(j, (h, (_, c, s)))| OrBranch {
+            h,
+            c: G::Scalar::conditional_select(&c, &c_real, is_real(j)),
+            s: G::Scalar::conditional_select(&s, &s_real, is_real(j)),
+        })
+        .collect())
+}
+
+/// Evaluates to Ok if `proof` shows knowledge of the discrete log of one of `statements`
+///
+/// Errors of a branch are reported with its index as the round.
+pub fn verify_or<G: Group>(
+    params: &PublicParams<G>,
+    statements: &[Statement<G>],
+    proof: OrProof<G>,
+) -> Result<(), Error> {
+    verify_or_with_context(params, statements, &[], proof)
+}
+
+/// Same as [`verify_or`], for proofs made by [`prove_or_with_context`]
+pub fn verify_or_with_context<G: Group>(
+    params: &PublicParams<G>,
+    statements: &[Statement<G>],
+    context: &[u8],
+    proof: OrProof<G>,
+) -> Result<(), Error> {
+    if proof.len() != statements.len() {
+        return Err(Error::WrongBranchCount {
+            expected: statements.len(),
+            found: proof.len(),
+        });
+    }
+
+    let group = params.group();
+    if !statements
+        .iter()
+        .all(|statement| group.contains(&statement.residue()))
+    {
+        return Err(Error::ResidueOutOfRange);
+    }
+    if let Some(round) = proof.iter().position(|branch| !group.contains(&branch.h)) {
+        return Err(Error::NonCanonicalCommitment { round });
+    }
+    // a challenge share c + order would add up to the same c
+    if let Some(round) = proof.iter().position(|branch| {
+        !group.is_canonical_scalar(&branch.c) || !group.is_canonical_scalar(&branch.s)
+    }) {
+        return Err(Error::NonCanonicalResponse { round });
+    }
+
+    // c = c_1 + ... + c_n (mod order)
+    let commitments: Vec<_> = proof.iter().map(|branch| branch.h).collect();
+    let c = get_challenge_by_hashing(group, statements, context, &commitments);
+    let sum = proof.iter().fold(group.scalar_from_u64(0), |sum, branch| {
+        group.scalar_add(&sum, &branch.c)
+    });
+    if sum != c {
+        return Err(Error::ChallengeMismatch);
+    }
+
+    for (round, (statement, OrBranch { h, c, s })) in statements.iter().zip(proof).enumerate() {
+        let lhs = group.exp_generator(&s); // g ^ s
+        let rhs = group.operate(&h, &group.exp(&statement.residue(), &c)); // h * y ^ c
+
+        if lhs != rhs {
+            return Err(Error::RoundFailed { round });
+        }
+    }
+
+    Ok(())
+}
+
+/// Derive challenge `c` from a transcript over every residue and every commitment
+fn get_challenge_by_hashing<G: Group>(
+    group: &G,
+    statements: &[Statement<G>],
+    context: &[u8],
+    commitments: &[G::Element],
+) -> G::Scalar {
+    let mut transcript = Transcript::new(PROTOCOL_LABEL);
+    transcript.append_message(b"group", &group.encode_parameters());
+    transcript.append_element(b"g", group, &group.generator());
+    transcript.append_message(b"n", &(statements.len() as u64).to_be_bytes());
+    for statement in statements {
+        transcript.append_element(b"y", group, &statement.residue());
+    }
+    transcript.append_message(b"context", context);
+    for h in commitments {
+        transcript.append_element(b"h", group, h);
+    }
+
+    group.challenge_scalar(&mut transcript, b"c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{large_params, params, prove_seeded},
+        Ristretto255,
+    };
+    use crypto_bigint::U256;
+    use rand_chacha::{
+        rand_core::{RngCore, SeedableRng},
+        ChaCha20Rng,
+    };
+
+    fn statements<G: Group>(
+        params: &PublicParams<G>,
+        witnesses: &[Witness<G>],
+    ) -> Vec<Statement<G>> {
+        witnesses
+            .iter()
+            .map(|witness| Statement::from_witness(params, witness))
+            .collect()
+    }
+
+    #[test]
+    fn test_positive() {
+        let params = params(47, 23, 2);
+        let witnesses = [5, 17, 9].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let statements = statements(&params, &witnesses);
+
+        for (index, witness) in witnesses.iter().enumerate() {
+            let proof = prove_or(&params, &statements, index, witness).unwrap();
+            assert_eq!(verify_or(&params, &statements, proof), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_negative() {
+        let params = large_params();
+        let witnesses = [5, 17, 9].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let mut statements = statements(&params, &witnesses);
+        let proof = prove_or(&params, &statements, 1, &witnesses[1]).unwrap();
+
+        // the proof is bound to the residues, not only to the one the prover knows
+        statements[0] = Statement::from_witness(&params, &Witness::new(&params, U256::from_u64(6)));
+        assert_eq!(
+            verify_or(&params, &statements, proof.clone()),
+            Err(Error::ChallengeMismatch)
+        );
+
+        assert_eq!(
+            verify_or(&params, &statements[..2], proof),
+            Err(Error::WrongBranchCount {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_tampered_branch() {
+        let params = params(59, 29, 4);
+        let witnesses = [3, 10].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let statements = statements(&params, &witnesses);
+        let mut proof = prove_or(&params, &statements, 0, &witnesses[0]).unwrap();
+        proof[1].s = params.group().scalar_add(&proof[1].s, &U256::ONE);
+
+        assert_eq!(
+            verify_or(&params, &statements, proof),
+            Err(Error::RoundFailed { round: 1 })
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_challenge() {
+        let params = params(59, 29, 4);
+        let witnesses = [3, 10].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let statements = statements(&params, &witnesses);
+        let mut proof = prove_or(&params, &statements, 0, &witnesses[0]).unwrap();
+        proof[1].c = proof[1].c.wrapping_add(&U256::from_u64(29)); // same share, not reduced
+
+        assert_eq!(
+            verify_or(&params, &statements, proof),
+            Err(Error::NonCanonicalResponse { round: 1 })
+        );
+    }
+
+    #[test]
+    fn test_wrong_index() {
+        let params = params(59, 29, 4);
+        let witnesses = [3, 10].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let statements = statements(&params, &witnesses);
+
+        assert_eq!(
+            prove_or(&params, &statements, 1, &witnesses[0]),
+            Err(Error::WrongWitness)
+        );
+        assert_eq!(
+            prove_or(&params, &statements, 2, &witnesses[0]),
+            Err(Error::WrongWitness)
+        );
+    }
+
+    #[test]
+    fn test_same_work_for_every_index() {
+        let params = params(59, 29, 4);
+        let witnesses = [3, 10, 17].map(|x| Witness::new(&params, U256::from_u64(x)));
+        let statements = statements(&params, &witnesses);
+
+        // as much randomness is drawn whichever branch is the real one
+        let rng_after = |index: usize| {
+            let mut rng = ChaCha20Rng::seed_from_u64(1);
+            prove_or_with_rng(
+                &params,
+                &statements,
+                index,
+                &witnesses[index],
+                b"",
+                &mut rng,
+            )
+            .unwrap();
+            rng.next_u64()
+        };
+        assert_eq!(rng_after(0), rng_after(2));
+    }
+
+    #[test]
+    fn test_ordered_statements() {
+        let params = PublicParams::new(Ristretto255::new()).unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let witnesses: Vec<_> = (0..4).map(|_| Witness::random(&params, &mut rng)).collect();
+        let statements = statements(&params, &witnesses);
+        let proof = prove_seeded(|rng| {
+            prove_or_with_rng(&params, &statements, 2, &witnesses[2], b"alice", rng).unwrap()
+        });
+        assert_eq!(
+            verify_or_with_context(&params, &statements, b"alice", proof.clone()),
+            Ok(())
+        );
+        assert_eq!(
+            verify_or_with_context(&params, &statements, b"bob", proof.clone()),
+            Err(Error::ChallengeMismatch)
+        );
+
+        // every branch still verifies on its own after a reordering, the sum of c_j does not
+        let reversed: Vec<_> = statements.iter().rev().copied().collect();
+        let proof: Vec<_> = proof.into_iter().rev().collect();
+        assert_eq!(
+            verify_or_with_context(&params, &reversed, b"alice", proof),
+            Err(Error::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_single_statement() {
+        // with n = 1 the only branch is the real one and gets all of c
+        let params = large_params();
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let statements = [Statement::from_witness(&params, &witness)];
+        let proof = prove_or(&params, &statements, 0, &witness).unwrap();
+
+        assert_eq!(verify_or(&params, &statements, proof), Ok(()));
+    }
+}
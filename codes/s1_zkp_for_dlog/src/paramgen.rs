@@ -0,0 +1,148 @@
+//! Generation of fresh Schnorr group parameters.
+//!
+//! Every random choice is drawn from the caller's RNG, so a seeded RNG reproduces the same group.
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::CryptoRngCore,
+    NonZero, Random, RandomMod, Uint,
+};
+
+use crate::{
+    primality::{is_probable_prime, MILLER_RABIN_ROUNDS},
+    validate_parameters_with_rng, ModpGroup, PublicParams,
+};
+
+/// Generate a safe prime p = 2q + 1 of exactly `bits` bits, returns (p, q)
+///
+/// # Panics
+///
+/// If `bits` is not in `3..=Uint::<LIMBS>::BITS`.
+pub fn generate_safe_prime<const LIMBS: usize>(
+    bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> (Uint<LIMBS>, Uint<LIMBS>) {
+    // 7 is the smallest safe prime we look for
+    assert!(
+        (3..=Uint::<LIMBS>::BITS).contains(&bits),
+        "safe primes have 3 to {} bits",
+        Uint::<LIMBS>::BITS
+    );
+
+    // q has bits - 1 bits, top and lowest bits set
+    let q_bits = bits - 1;
+    let mask = Uint::<LIMBS>::MAX.shr_vartime(Uint::<LIMBS>::BITS - q_bits);
+    let top = Uint::<LIMBS>::ONE.shl_vartime(q_bits - 1);
+
+    loop {
+        let q = Uint::<LIMBS>::random(rng)
+            .bitand(&mask)
+            .bitor(&top)
+            .bitor(&Uint::ONE);
+        let p = q.shl_vartime(1).wrapping_add(&Uint::ONE);
+
+        // one cheap round on both first, most candidates fail here
+        if !is_probable_prime(&q, 1, rng) || !is_probable_prime(&p, 1, rng) {
+            continue;
+        }
+
+        if is_probable_prime(&q, MILLER_RABIN_ROUNDS, rng)
+            && is_probable_prime(&p, MILLER_RABIN_ROUNDS, rng)
+        {
+            return (p, q);
+        }
+    }
+}
+
+/// Find a generator of the subgroup of order q mod p, for prime q dividing p - 1
+pub fn find_generator<const LIMBS: usize>(
+    modulus: &Uint<LIMBS>,
+    order: &Uint<LIMBS>,
+    rng: &mut impl CryptoRngCore,
+) -> Uint<LIMBS> {
+    // g = h^((p - 1) / q) has order 1 or q for any h, try again on 1
+    let p_minus_1 = modulus.wrapping_sub(&Uint::ONE);
+    let cofactor = p_minus_1.wrapping_div(order);
+    let params = DynResidueParams::new(modulus);
+    let one = DynResidue::one(params).retrieve();
+
+    // h is drawn from [2, p - 1)
+    let range = NonZero::new(modulus.wrapping_sub(&Uint::from_u8(3))).unwrap();
+
+    loop {
+        let h = Uint::random_mod(rng, &range).wrapping_add(&Uint::from_u8(2));
+        let generator = DynResidue::new(&h, params).pow(&cofactor).retrieve();
+
+        if generator != one {
+            return generator;
+        }
+    }
+}
+
+/// Generate a Schnorr group over a fresh safe prime of `bits` bits, validated once here
+///
+/// # Panics
+///
+/// As [`generate_safe_prime`], if `bits` is not in `3..=Uint::<LIMBS>::BITS`.
+pub fn generate_parameters<const LIMBS: usize>(
+    bits: usize,
+    rng: &mut impl CryptoRngCore,
+) -> PublicParams<ModpGroup<LIMBS>> {
+    let (modulus, order) = generate_safe_prime(bits, rng);
+    let generator = find_generator(&modulus, &order, rng);
+
+    // generation guarantees everything checked here, a failure is a bug in this module
+    let group = validate_parameters_with_rng(&modulus, &order, &generator, rng)
+        .and_then(|()| ModpGroup::new(modulus, order, generator))
+        .expect("generated parameters are invalid");
+
+    PublicParams::new_unchecked(group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prove_schnorr, verify_schnorr, Group, Witness};
+    use crypto_bigint::{U128, U256};
+    use rand_chacha::{
+        rand_core::{RngCore, SeedableRng},
+        ChaCha20Rng,
+    };
+
+    #[test]
+    fn test_safe_prime() {
+        let mut rng = ChaCha20Rng::seed_from_u64(8);
+        for bits in [3, 8, 64, 128] {
+            let (p, q) = generate_safe_prime::<{ U128::LIMBS }>(bits, &mut rng);
+
+            assert_eq!(p.bits(), bits);
+            assert_eq!(q.shl_vartime(1).wrapping_add(&U128::ONE), p);
+        }
+    }
+
+    #[test]
+    fn test_generate_parameters() {
+        let mut rng = ChaCha20Rng::seed_from_u64(8);
+        let params = generate_parameters::<{ U256::LIMBS }>(128, &mut rng);
+
+        assert_eq!(params.group().modulus().bits(), 128);
+        assert!(params.group().validate().is_ok());
+
+        let (statement, proof) = prove_schnorr(&params, &Witness::new(&params, U256::from_u64(42)));
+        assert_eq!(verify_schnorr(&params, &statement, proof), Ok(()));
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let generate = |seed| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let params = generate_parameters::<{ U256::LIMBS }>(96, &mut rng);
+
+            // validation draws from `rng` too, nothing comes from the OS
+            (params, rng.next_u64())
+        };
+
+        assert_eq!(generate(1), generate(1));
+        assert_ne!(generate(1).0, generate(2).0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Miller-Rabin probabilistic primality test.
+
+use crypto_bigint::{
+    modular::runtime_mod::{DynResidue, DynResidueParams},
+    rand_core::CryptoRngCore,
+    Limb, NonZero, RandomMod, Uint,
+};
+
+/// Rounds of Miller-Rabin, each one lets a composite through with probability at most 1/4
+pub const MILLER_RABIN_ROUNDS: usize = 64;
+
+// trial division by these before running any exponentiation
+const SMALL_PRIMES: [u32; 53] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241,
+];
+
+/// Whether `n` is prime, with error probability at most 4^-rounds for composite `n`
+pub fn is_probable_prime<const LIMBS: usize>(
+    n: &Uint<LIMBS>,
+    rounds: usize,
+    rng: &mut impl CryptoRngCore,
+) -> bool {
+    let three = Uint::<LIMBS>::from_u8(3);
+    if *n < three {
+        return *n == Uint::from_u8(2);
+    }
+
+    for prime in SMALL_PRIMES {
+        let (_, rem) = n.div_rem_limb(NonZero::new(Limb::from_u32(prime)).unwrap());
+        if rem == Limb::ZERO {
+            return *n == Uint::from_u32(prime);
+        }
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let n_minus_1 = n.wrapping_sub(&Uint::ONE);
+    let s = n_minus_1.trailing_zeros();
+    let d = n_minus_1.shr_vartime(s);
+
+    // n is odd, so montgomery form is fine
+    let params = DynResidueParams::new(n);
+    let one = DynResidue::one(params);
+    let minus_one = DynResidue::new(&n_minus_1, params);
+
+    // bases are drawn from [2, n - 2], n > 241 here after trial division
+    let base_range = NonZero::new(n.wrapping_sub(&three)).unwrap();
+
+    'witness: for _ in 0..rounds {
+        let base = Uint::random_mod(rng, &base_range).wrapping_add(&Uint::from_u8(2));
+        let mut x = DynResidue::new(&base, params).pow(&d);
+        if x == one || x == minus_one {
+            continue;
+        }
+
+        for _ in 1..s {
+            x = x.square();
+            if x == minus_one {
+                continue 'witness;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto_bigint::{rand_core::OsRng, U256};
+
+    fn is_prime(n: u64) -> bool {
+        is_probable_prime(&U256::from_u64(n), MILLER_RABIN_ROUNDS, &mut OsRng)
+    }
+
+    #[test]
+    fn test_small() {
+        let primes: Vec<_> = (0..300).filter(|n| is_prime(*n)).collect();
+        let expected: Vec<_> = (0..300u64)
+            .filter(|n| *n >= 2 && (2..*n).all(|d| n % d != 0))
+            .collect();
+
+        assert_eq!(primes, expected);
+    }
+
+    #[test]
+    fn test_large() {
+        assert!(is_prime(2305843009213693951)); // 2^61 - 1
+        assert!(is_prime(2305843009213691579));
+        assert!(!is_prime(2305843009213693953)); // 2^61 + 1 = 3 * 768614336404564651
+        assert!(!is_prime(3825123056546413051)); // strong pseudoprime to every base up to 23
+        assert!(!is_prime(1000000016000000063)); // 1000000007 * 1000000009
+    }
+}
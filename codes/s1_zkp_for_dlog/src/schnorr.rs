@@ -0,0 +1,219 @@
+//! Schnorr proof of knowledge of discrete log.
+//!
+//! Instead of 100 rounds with 1-bit challenges, the prover commits once to `h = g^r`
+//! and answers a single full-width challenge `c` with `s = r + c * x (mod order)`.
+//! The verifier checks `g^s = h * y^c`, so the proof is just one element and one exponent.
+
+use crypto_bigint::rand_core::{CryptoRngCore, OsRng};
+
+use crate::{new_transcript, Error, Group, PublicParams, Statement, Witness};
+
+// protocol label absorbed first into the transcript of the schnorr proof
+const PROTOCOL_LABEL: &[u8] = b"dlog-schnorr";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        bound = "G::Element: crate::encoding::FixedBytes, G::Scalar: crate::encoding::FixedBytes"
+    )
+)]
+pub struct SchnorrProof<G: Group> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub h: G::Element, // h = g^r
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    pub s: G::Scalar, // s = (r + c * x) (mod order)
+}
+
+/// Prove that we know secret x such that y = g^x with a single challenge
+///
+/// Returns (statement y, proof)
+pub fn prove_schnorr<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+) -> (Statement<G>, SchnorrProof<G>) {
+    prove_schnorr_with_context(params, witness, &[])
+}
+
+/// Same as [`prove_schnorr`], with the proof bound to a caller chosen `context`
+pub fn prove_schnorr_with_context<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    context: &[u8],
+) -> (Statement<G>, SchnorrProof<G>) {
+    prove_schnorr_with_rng(params, witness, context, &mut OsRng)
+}
+
+/// Same as [`prove_schnorr_with_context`], drawing the nonce from `rng` instead of the OS
+pub fn prove_schnorr_with_rng<G: Group>(
+    params: &PublicParams<G>,
+    witness: &Witness<G>,
+    context: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> (Statement<G>, SchnorrProof<G>) {
+    let group = params.group();
+    let secret = witness.expose_secret();
+
+    // y = g^x
+    let residue = group.exp_generator(secret);
+
+    // commit to h = g^r
+    let r = group.random_scalar(rng);
+    let h = group.exp_generator(&r);
+
+    // s = r + c * x (mod order)
+    let c = get_challenge_by_hashing(group, &residue, context, &h);
+    let s = group.scalar_add(&r, &group.scalar_mul(&c, secret));
+
+    (Statement::new(residue), SchnorrProof { h, s })
+}
+
+/// Evaluates to Ok if `proof` shows knowledge of x such that y = g^x
+///
+/// Errors are reported as for a proof of a single round 0.
+pub fn verify_schnorr<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    proof: SchnorrProof<G>,
+) -> Result<(), Error> {
+    verify_schnorr_with_context(params, statement, &[], proof)
+}
+
+/// Same as [`verify_schnorr`], for proofs made by [`prove_schnorr_with_context`]
+pub fn verify_schnorr_with_context<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    context: &[u8],
+    proof: SchnorrProof<G>,
+) -> Result<(), Error> {
+    let group = params.group();
+    let residue = statement.residue();
+    let SchnorrProof { h, s } = proof;
+    if !group.contains(&residue) {
+        return Err(Error::ResidueOutOfRange);
+    }
+    if !group.contains(&h) {
+        return Err(Error::NonCanonicalCommitment { round: 0 });
+    }
+    if !group.is_canonical_scalar(&s) {
+        return Err(Error::NonCanonicalResponse { round: 0 });
+    }
+
+    let c = get_challenge_by_hashing(group, &residue, context, &h);
+
+    let lhs = group.exp_generator(&s); // g ^ s
+    let rhs = group.operate(&h, &group.exp(&residue, &c)); // h * y ^ c
+
+    if lhs != rhs {
+        return Err(Error::RoundFailed { round: 0 });
+    }
+
+    Ok(())
+}
+
+/// Derive challenge `c` from a transcript over the statement and the commitment
+fn get_challenge_by_hashing<G: Group>(
+    group: &G,
+    residue: &G::Element,
+    context: &[u8],
+    h: &G::Element,
+) -> G::Scalar {
+    let mut transcript = new_transcript(PROTOCOL_LABEL, group, residue, context);
+    transcript.append_element(b"h", group, h);
+
+    group.challenge_scalar(&mut transcript, b"c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{large_params, params};
+    use crypto_bigint::U256;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn test_positive() {
+        let params = params(47, 23, 2);
+        let witness = Witness::new(&params, U256::from_u64(17));
+        let (statement, proof) = prove_schnorr(&params, &witness);
+
+        assert_eq!(verify_schnorr(&params, &statement, proof), Ok(()));
+    }
+
+    #[test]
+    fn test_negative() {
+        let params = large_params();
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, proof) = prove_schnorr(&params, &witness);
+
+        assert_eq!(
+            verify_schnorr(
+                &params,
+                &Statement::new(statement.residue().wrapping_add(&U256::ONE)),
+                proof
+            ),
+            Err(Error::ResidueOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_tampered_response() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, mut proof) = prove_schnorr(&params, &witness);
+        proof.s = params.group().scalar_add(&proof.s, &U256::ONE);
+
+        assert_eq!(
+            verify_schnorr(&params, &statement, proof),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_response() {
+        let params = params(59, 29, 4);
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, mut proof) = prove_schnorr(&params, &witness);
+        proof.s = proof.s.wrapping_add(&U256::from_u64(29)); // same exponent, not reduced
+
+        assert_eq!(
+            verify_schnorr(&params, &statement, proof),
+            Err(Error::NonCanonicalResponse { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_seeded_rng() {
+        let params = params(47, 23, 2);
+        let witness = Witness::new(&params, U256::from_u64(17));
+        let prove_seeded = |seed| {
+            prove_schnorr_with_rng(
+                &params,
+                &witness,
+                b"",
+                &mut ChaCha20Rng::seed_from_u64(seed),
+            )
+        };
+
+        let (statement, proof) = prove_seeded(1);
+        assert_eq!(prove_seeded(1), (statement, proof));
+        assert_eq!(verify_schnorr(&params, &statement, proof), Ok(()));
+    }
+
+    #[test]
+    fn test_wrong_context() {
+        let params = large_params();
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, proof) = prove_schnorr_with_context(&params, &witness, b"alice");
+
+        assert_eq!(
+            verify_schnorr_with_context(&params, &statement, b"alice", proof),
+            Ok(())
+        );
+        assert_eq!(
+            verify_schnorr_with_context(&params, &statement, b"bob", proof),
+            Err(Error::RoundFailed { round: 0 })
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Serde support, behind the `serde` feature.
+//!
+//! Every value is its [`FixedBytes`](crate::encoding::FixedBytes) encoding: a hex string in
+//! human-readable formats (JSON, TOML, ...) and raw bytes in binary ones. Integers are
+//! fixed-width big-endian, curve points SEC1 uncompressed and ristretto255 points their
+//! canonical encoding.
+//!
+//! Serde has no group at hand, so deserialized values are only checked for their format.
+//! The verifiers still reject elements outside the group and responses not reduced mod
+//! its order.
+
+/// `#[serde(with = "...")]` helpers for any [`FixedBytes`](crate::encoding::FixedBytes) field,
+/// e.g. a residue `y: U2048` or a point in a caller's own struct
+pub mod fixed_bytes {
+    use std::fmt;
+
+    use serde::{
+        de::{self, SeqAccess, Visitor},
+        Deserialize, Deserializer, Serializer,
+    };
+
+    use crate::encoding::FixedBytes;
+
+    pub fn serialize<T: FixedBytes, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let bytes = value.to_fixed_bytes();
+
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(bytes))
+        } else {
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+
+    pub fn deserialize<'de, T: FixedBytes, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            let string = String::deserialize(deserializer)?;
+            hex::decode(string).map_err(de::Error::custom)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
+
+        T::from_fixed_bytes(&bytes).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Bytes(&bytes), &"a fixed-width encoding")
+        })
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("bytes")
+        }
+
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(bytes)
+        }
+
+        // formats without a native byte string send a sequence instead
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+
+            Ok(bytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::U256;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        dleq::{prove_dleq, verify_dleq, DleqProof, DleqStatement},
+        encoding::FixedBytes,
+        group::standard::P256,
+        prove, prove_schnorr, verify, verify_schnorr, Group, ModpGroup, ModpParameters, Proofs,
+        PublicParams, Ristretto255, SchnorrProof, Statement, WeierstrassCurve, Witness,
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    fn parameters() -> ModpParameters<{ U256::LIMBS }> {
+        ModpParameters {
+            modulus: U256::from_u64(59),
+            order: U256::from_u64(29),
+            generator: U256::from_u64(4),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Published {
+        statement: Statement<ModpGroup<{ U256::LIMBS }>>,
+        parameters: ModpParameters<{ U256::LIMBS }>,
+    }
+
+    #[test]
+    fn test_json_is_hex() {
+        let json = serde_json::to_value(parameters()).unwrap();
+
+        assert_eq!(
+            json["modulus"],
+            "000000000000000000000000000000000000000000000000000000000000003b"
+        );
+        assert_eq!(
+            serde_json::from_value::<ModpParameters<{ U256::LIMBS }>>(json).unwrap(),
+            parameters()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_json() {
+        let params = PublicParams::try_from(parameters()).unwrap();
+        let (statement, proofs) = prove(&params, &Witness::new(&params, U256::from_u64(10)));
+        let published = Published {
+            statement,
+            parameters: parameters(),
+        };
+
+        let published_json = serde_json::to_string(&published).unwrap();
+        let proofs_json = serde_json::to_string(&proofs).unwrap();
+        let group_json = serde_json::to_string(params.group()).unwrap();
+
+        let published: Published = serde_json::from_str(&published_json).unwrap();
+        let proofs: Proofs<ModpGroup<{ U256::LIMBS }>> =
+            serde_json::from_str(&proofs_json).unwrap();
+        let group: ModpGroup<{ U256::LIMBS }> = serde_json::from_str(&group_json).unwrap();
+
+        // the statement is the bare residue
+        assert_eq!(
+            serde_json::to_value(statement).unwrap(),
+            hex::encode(statement.residue().to_fixed_bytes())
+        );
+        assert_eq!(published.parameters.group(), Ok(group));
+        assert_eq!(
+            verify(
+                &PublicParams::new(group).unwrap(),
+                &published.statement,
+                proofs
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_round_trip_binary() {
+        let params = PublicParams::try_from(parameters()).unwrap();
+        let (statement, proof) = prove_schnorr(&params, &Witness::new(&params, U256::from_u64(10)));
+
+        let bytes = bincode::serialize(&proof).unwrap();
+        assert_eq!(bytes.len(), 2 * (8 + 32)); // length prefix and raw bytes, no hex
+
+        let decoded: SchnorrProof<ModpGroup<{ U256::LIMBS }>> =
+            bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(verify_schnorr(&params, &statement, decoded), Ok(()));
+
+        let group_bytes = bincode::serialize(params.group()).unwrap();
+        assert_eq!(
+            bincode::deserialize::<ModpGroup<{ U256::LIMBS }>>(&group_bytes).unwrap(),
+            *params.group()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_dleq() {
+        let params = PublicParams::try_from(parameters()).unwrap();
+        let group = params.group();
+        let g2 = group.exp(&group.generator(), &U256::from_u64(3));
+        let witness = Witness::new(&params, U256::from_u64(10));
+        let (statement, proof) = prove_dleq(&params, &group.generator(), &g2, &witness);
+
+        let json = serde_json::to_value((statement, proof)).unwrap();
+        assert_eq!(json[0]["g2"], hex::encode(g2.to_fixed_bytes()));
+
+        let decoded: (DleqStatement<ModpGroup<{ U256::LIMBS }>>, DleqProof<_>) =
+            serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, (statement, proof));
+        assert_eq!(verify_dleq(&params, &decoded.0, decoded.1), Ok(()));
+    }
+
+    #[test]
+    fn test_round_trip_curves() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+
+        let params = PublicParams::new_unchecked(P256.curve().unwrap());
+        let (statement, proof) = prove_schnorr(&params, &Witness::random(&params, &mut rng));
+        let json = serde_json::to_string(&(statement, proof)).unwrap();
+        let decoded: (
+            Statement<WeierstrassCurve<{ U256::LIMBS }>>,
+            SchnorrProof<_>,
+        ) = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, (statement, proof));
+        assert_eq!(verify_schnorr(&params, &decoded.0, decoded.1), Ok(()));
+
+        let params = PublicParams::new(Ristretto255::new()).unwrap();
+        let (statement, proof) = prove_schnorr(&params, &Witness::random(&params, &mut rng));
+        let bytes = bincode::serialize(&(statement, proof)).unwrap();
+        let decoded: (Statement<Ristretto255>, SchnorrProof<_>) =
+            bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, (statement, proof));
+        assert_eq!(verify_schnorr(&params, &decoded.0, decoded.1), Ok(()));
+
+        // not a canonical ristretto255 encoding
+        let json = format!(r#""{}""#, "ff".repeat(32));
+        assert!(serde_json::from_str::<Statement<Ristretto255>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_reject_malformed() {
+        // too short
+        assert!(serde_json::from_str::<ModpParameters<{ U256::LIMBS }>>(
+            r#"{"modulus":"3b","order":"1d","generator":"04"}"#
+        )
+        .is_err());
+
+        // not hex
+        let json = serde_json::to_string(&parameters())
+            .unwrap()
+            .replace("3b", "zz");
+        assert!(serde_json::from_str::<ModpParameters<{ U256::LIMBS }>>(&json).is_err());
+
+        // a group is only deserialized from usable parameters
+        let json = serde_json::to_string(&ModpParameters {
+            modulus: U256::from_u64(60),
+            ..parameters()
+        })
+        .unwrap();
+        assert!(serde_json::from_str::<ModpGroup<{ U256::LIMBS }>>(&json).is_err());
+    }
+}
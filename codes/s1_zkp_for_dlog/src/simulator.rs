@@ -0,0 +1,172 @@
+//! Honest-verifier zero-knowledge, made concrete.
+//!
+//! Knowing the challenge bits in advance, anyone can write down accepting rounds without x:
+//! pick `s` uniformly and solve `g^s = h * y^b` for `h = g^s * y^-b`.
+//! These rounds are distributed exactly like the ones of an honest prover facing the same
+//! challenges (`h` uniform in the group, `s` the unique answer), so a transcript with an
+//! honest verifier teaches nothing a verifier could not have produced alone.
+//!
+//! Note that the simulated rounds only verify against the given challenges with
+//! [`verify_transcript`], never as a non-interactive proof: there the challenges are hashed
+//! from the commitments, which the simulator cannot control.
+
+use crypto_bigint::rand_core::{CryptoRngCore, OsRng};
+
+use crate::{check_elements, check_rounds, Error, Group, Proof, Proofs, PublicParams, Statement};
+
+/// Accepting rounds for statement y = g^x and the given challenge bits, without knowing x
+pub fn simulate<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    challenges: &[bool],
+) -> Proofs<G> {
+    simulate_with_rng(params, statement, challenges, &mut OsRng)
+}
+
+/// Same as [`simulate`], drawing every `s` from `rng`
+pub fn simulate_with_rng<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    challenges: &[bool],
+    rng: &mut impl CryptoRngCore,
+) -> Proofs<G> {
+    let group = params.group();
+    let residue_inverse = group.invert(&statement.residue());
+
+    challenges
+        .iter()
+        .map(|bit| {
+            let s = group.random_scalar(rng);
+
+            // h = g^s * y^-b
+            let h = if *bit {
+                group.operate(&group.exp_generator(&s), &residue_inverse)
+            } else {
+                group.exp_generator(&s)
+            };
+
+            Proof { h, s }
+        })
+        .collect()
+}
+
+/// Check an interactive transcript: round `i` answers challenge bit `challenges[i]`
+///
+/// A transcript without exactly one round per challenge is rejected with
+/// [`Error::WrongRoundCount`].
+pub fn verify_transcript<G: Group>(
+    params: &PublicParams<G>,
+    statement: &Statement<G>,
+    challenges: &[bool],
+    proofs: &[Proof<G>],
+) -> Result<(), Error> {
+    if proofs.len() != challenges.len() {
+        return Err(Error::WrongRoundCount {
+            expected: challenges.len(),
+            found: proofs.len(),
+        });
+    }
+
+    let group = params.group();
+    let residue = statement.residue();
+    check_elements(group, &residue, proofs)?;
+    check_rounds(group, &residue, proofs, challenges)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::{test_util::params, Prover, Witness};
+    use crypto_bigint::{rand_core::RngCore, U256};
+
+    const SAMPLES: usize = 20000;
+
+    fn random_challenges(n: usize) -> Vec<bool> {
+        (0..n).map(|_| OsRng.next_u32() & 1 == 1).collect()
+    }
+
+    // relative frequency of each round (h, s)
+    fn histogram(rounds: impl Iterator<Item = (U256, U256)>) -> HashMap<(U256, U256), f64> {
+        let mut counts = HashMap::new();
+        for round in rounds {
+            *counts.entry(round).or_insert(0.0) += 1.0 / SAMPLES as f64;
+        }
+
+        counts
+    }
+
+    fn total_variation(lhs: &HashMap<(U256, U256), f64>, rhs: &HashMap<(U256, U256), f64>) -> f64 {
+        let keys: HashSet<_> = lhs.keys().chain(rhs.keys()).collect();
+        let distance: f64 = keys
+            .into_iter()
+            .map(|key| (lhs.get(key).unwrap_or(&0.0) - rhs.get(key).unwrap_or(&0.0)).abs())
+            .sum();
+
+        distance / 2.0
+    }
+
+    #[test]
+    fn test_simulated_rounds_accept() {
+        let params = params(11, 5, 4);
+        let statement = Statement::from_witness(&params, &Witness::new(&params, U256::from_u64(3)));
+        let challenges = random_challenges(100);
+        let proofs = simulate(&params, &statement, &challenges);
+
+        assert_eq!(
+            verify_transcript(&params, &statement, &challenges, &proofs),
+            Ok(())
+        );
+
+        // but only against these challenges
+        let flipped: Vec<_> = challenges.iter().map(|bit| !bit).collect();
+        assert!(verify_transcript(&params, &statement, &flipped, &proofs).is_err());
+    }
+
+    #[test]
+    fn test_wrong_round_count() {
+        let params = params(11, 5, 4);
+        let statement = Statement::from_witness(&params, &Witness::new(&params, U256::from_u64(3)));
+        let challenges = random_challenges(100);
+        let proofs = simulate(&params, &statement, &challenges);
+
+        assert_eq!(
+            verify_transcript(&params, &statement, &challenges[..99], &proofs),
+            Err(Error::WrongRoundCount {
+                expected: 99,
+                found: 100
+            })
+        );
+    }
+
+    #[test]
+    fn test_same_distribution() {
+        // 4 has order 5 mod 11, so there are only 5 possible rounds per challenge
+        let params = params(11, 5, 4);
+        let prover = Prover::new(&params, Witness::new(&params, U256::from_u64(3)));
+        let statement = prover.statement();
+
+        for bit in [false, true] {
+            let real = histogram((0..SAMPLES).map(|_| {
+                let (round, h) = prover.commit();
+                (h, round.respond(bit))
+            }));
+            let simulated = histogram(
+                simulate(&params, &statement, &vec![bit; SAMPLES])
+                    .into_iter()
+                    .map(|proof| (proof.h, proof.s)),
+            );
+
+            // both are uniform over the same 5 accepting rounds
+            assert_eq!(real.len(), 5);
+            let mut support: Vec<_> = real.keys().collect();
+            let mut simulated_support: Vec<_> = simulated.keys().collect();
+            support.sort();
+            simulated_support.sort();
+            assert_eq!(support, simulated_support);
+
+            assert!(total_variation(&real, &simulated) < 0.03);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Typed inputs of the proofs: validated parameters, the public statement and the secret witness.
+//!
+//! Elements and exponents of [`ModpGroup`] are both `Uint`s, so a residue, a secret and a
+//! modulus are easy to swap by accident. Wrapping each of them makes that a compile error.
+
+use std::fmt;
+
+use crypto_bigint::rand_core::CryptoRngCore;
+use zeroize::Zeroize;
+
+use crate::{CurveParameters, Group, ModpGroup, ModpParameters, ParameterError, WeierstrassCurve};
+
+/// A group whose parameters passed [`Group::validate`]
+///
+/// The group owns its precomputation (e.g. the Montgomery setup of [`ModpGroup`]),
+/// so building this once and reusing it for every proof pays for validation and setup once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicParams<G: Group> {
+    group: G,
+}
+
+impl<G: Group> PublicParams<G> {
+    /// Validate `group`, see [`Group::validate`]
+    pub fn new(group: G) -> Result<Self, ParameterError> {
+        group.validate()?;
+
+        Ok(Self { group })
+    }
+
+    /// Same as [`Self::new`], drawing the Miller-Rabin bases from `rng` instead of the OS
+    pub fn new_with_rng(group: G, rng: &mut impl CryptoRngCore) -> Result<Self, ParameterError> {
+        group.validate_with_rng(rng)?;
+
+        Ok(Self { group })
+    }
+
+    /// Skip validation, for parameters already known to be good (e.g. [`crate::group::standard`])
+    pub fn new_unchecked(group: G) -> Self {
+        Self { group }
+    }
+
+    pub fn group(&self) -> &G {
+        &self.group
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<ModpParameters<LIMBS>> for PublicParams<ModpGroup<LIMBS>> {
+    type Error = ParameterError;
+
+    fn try_from(parameters: ModpParameters<LIMBS>) -> Result<Self, ParameterError> {
+        parameters.validate()?;
+
+        Ok(Self::new_unchecked(parameters.group()?))
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<CurveParameters<LIMBS>> for PublicParams<WeierstrassCurve<LIMBS>> {
+    type Error = ParameterError;
+
+    fn try_from(parameters: CurveParameters<LIMBS>) -> Result<Self, ParameterError> {
+        parameters.validate()?;
+
+        Ok(Self::new_unchecked(parameters.curve()?))
+    }
+}
+
+/// The public statement: residue y = g^x
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(transparent, bound = "G::Element: crate::encoding::FixedBytes")
+)]
+pub struct Statement<G: Group> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fixed_bytes"))]
+    residue: G::Element,
+}
+
+impl<G: Group> Statement<G> {
+    /// Statement of residue y, membership in the group is checked by the verifier
+    pub fn new(residue: G::Element) -> Self {
+        Self { residue }
+    }
+
+    /// y = g^x for the secret of `witness`
+    pub fn from_witness(params: &PublicParams<G>, witness: &Witness<G>) -> Self {
+        Self::new(params.group().exp_generator(&witness.secret))
+    }
+
+    pub fn residue(&self) -> G::Element {
+        self.residue
+    }
+}
+
+// derives would require G itself to be Clone / Eq / Debug
+impl<G: Group> Clone for Statement<G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: Group> Copy for Statement<G> {}
+
+impl<G: Group> PartialEq for Statement<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.residue == other.residue
+    }
+}
+
+impl<G: Group> Eq for Statement<G> {}
+
+impl<G: Group> fmt::Debug for Statement<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Statement").field(&self.residue).finish()
+    }
+}
+
+/// The secret x, zeroized on drop and never printed
+pub struct Witness<G: Group> {
+    secret: G::Scalar,
+}
+
+impl<G: Group> Witness<G> {
+    /// The secret x, reduced mod the order so that every prover can rely on x < order
+    pub fn new(params: &PublicParams<G>, mut secret: G::Scalar) -> Self {
+        let reduced = params.group().reduce_scalar(&secret);
+        secret.zeroize();
+
+        Self { secret: reduced }
+    }
+
+    /// Uniformly random secret
+    pub fn random(params: &PublicParams<G>, rng: &mut impl CryptoRngCore) -> Self {
+        Self {
+            secret: params.group().random_scalar(rng),
+        }
+    }
+
+    /// The secret itself, for storing it or handing it to code outside this crate
+    pub fn expose_secret(&self) -> &G::Scalar {
+        &self.secret
+    }
+}
+
+impl<G: Group> Clone for Witness<G> {
+    fn clone(&self) -> Self {
+        Self {
+            secret: self.secret,
+        }
+    }
+}
+
+impl<G: Group> PartialEq for Witness<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.secret == other.secret
+    }
+}
+
+impl<G: Group> Eq for Witness<G> {}
+
+impl<G: Group> fmt::Debug for Witness<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Witness(<redacted>)")
+    }
+}
+
+impl<G: Group> Drop for Witness<G> {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::params;
+    use crypto_bigint::{NonZero, U256};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn test_public_params() {
+        let bad = ModpGroup::new(U256::from_u64(47), U256::from_u64(23), U256::from_u64(5));
+        assert_eq!(
+            PublicParams::new(bad.unwrap()),
+            Err(ParameterError::WrongGeneratorOrder)
+        );
+
+        let parameters = params(47, 23, 2).group().parameters();
+        assert_eq!(PublicParams::try_from(parameters), Ok(params(47, 23, 2)));
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        assert_eq!(
+            PublicParams::new_with_rng(*params(47, 23, 2).group(), &mut rng),
+            Ok(params(47, 23, 2))
+        );
+        assert_eq!(
+            PublicParams::new_with_rng(bad.unwrap(), &mut rng),
+            Err(ParameterError::WrongGeneratorOrder)
+        );
+    }
+
+    #[test]
+    fn test_statement() {
+        let witness = Witness::new(&params(47, 23, 2), U256::from_u64(17));
+
+        assert_eq!(
+            Statement::from_witness(&params(47, 23, 2), &witness).residue(),
+            U256::from_u64(36) // 2^17 = 36 (mod 47)
+        );
+    }
+
+    #[test]
+    fn test_witness_is_redacted() {
+        let witness = Witness::new(&params(47, 23, 2), U256::from_u64(17));
+
+        assert_eq!(format!("{witness:?}"), "Witness(<redacted>)");
+        assert_eq!(witness.clone(), witness);
+        assert_eq!(*witness.expose_secret(), U256::from_u64(17));
+    }
+
+    #[test]
+    fn test_witness_is_reduced() {
+        let params = params(47, 23, 2);
+
+        // 63 = 17 (mod 23)
+        let witness = Witness::new(&params, U256::from_u64(63));
+        assert_eq!(*witness.expose_secret(), U256::from_u64(17));
+        assert_eq!(
+            Witness::new(&params, U256::MAX).expose_secret(),
+            &U256::MAX.rem(&NonZero::new(U256::from_u64(23)).unwrap())
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Fixtures shared by the unit tests.
+
+use std::fmt::Debug;
+
+use crypto_bigint::U256;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+use crate::{ModpGroup, PublicParams};
+
+/// The validated subgroup of order q generated by g mod p, e.g. the toy groups (47, 23, 2)
+pub fn params(p: u64, q: u64, g: u64) -> PublicParams<ModpGroup<{ U256::LIMBS }>> {
+    let group = ModpGroup::new(U256::from_u64(p), U256::from_u64(q), U256::from_u64(g));
+
+    PublicParams::new(group.unwrap()).unwrap()
+}
+
+/// The subgroup of order q = (p - 1) / 2 mod the 61-bit safe prime p, generated by 4
+///
+/// For tests that need a rejection to be certain: in a toy group a challenge is 0, or a forged
+/// one matches, with probability 1 / q, so such a test would fail every few dozen runs.
+pub fn large_params() -> PublicParams<ModpGroup<{ U256::LIMBS }>> {
+    params(2305843009213691579, 1152921504606845789, 4)
+}
+
+/// What `prove` returns with a seeded RNG, after checking that the same seed gives the same output
+pub fn prove_seeded<T: PartialEq + Debug>(prove: impl Fn(&mut ChaCha20Rng) -> T) -> T {
+    let output = prove(&mut ChaCha20Rng::seed_from_u64(2));
+    assert_eq!(prove(&mut ChaCha20Rng::seed_from_u64(2)), output);
+
+    output
+}
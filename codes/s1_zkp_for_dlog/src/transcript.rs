@@ -0,0 +1,138 @@
+//! Fiat-Shamir transcript on top of blake3.
+//!
+//! Every message is absorbed together with a label, and challenges are squeezed from everything
+//! absorbed so far. Each squeeze is absorbed back, so later challenges depend on earlier ones.
+//! Labels and messages are length-prefixed, so `("ab", "c")` and `("a", "bc")` never collide.
+
+use crypto_bigint::{NonZero, Uint};
+
+use crate::Group;
+
+/// blake3 key derivation context, separating our transcripts from any other use of blake3
+const DOMAIN_SEPARATOR: &str = "s1_zkp_for_dlog 2023 fiat-shamir transcript v1";
+
+// operation tags written before each labelled frame
+const OP_PROTOCOL: u8 = 0;
+const OP_ABSORB: u8 = 1;
+const OP_SQUEEZE: u8 = 2;
+
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: blake3::Hasher,
+}
+
+impl Transcript {
+    /// Start a transcript for the protocol named by `protocol_label`
+    pub fn new(protocol_label: &[u8]) -> Self {
+        let mut transcript = Self {
+            hasher: blake3::Hasher::new_derive_key(DOMAIN_SEPARATOR),
+        };
+        transcript.write_frame(OP_PROTOCOL, protocol_label, &[]);
+
+        transcript
+    }
+
+    /// Absorb `message` under `label`
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.write_frame(OP_ABSORB, label, message);
+    }
+
+    /// Absorb an element of `group` under `label`
+    pub fn append_element<G: Group>(&mut self, label: &[u8], group: &G, element: &G::Element) {
+        self.append_message(label, &group.encode_element(element));
+    }
+
+    /// Fill `dest` with challenge bytes derived from everything absorbed so far
+    pub fn challenge_bytes(&mut self, label: &[u8], dest: &mut [u8]) {
+        let mut squeezer = self.hasher.clone();
+        squeezer.update(&[OP_SQUEEZE]);
+        squeezer.update(&(label.len() as u64).to_le_bytes());
+        squeezer.update(label);
+        squeezer.update(&(dest.len() as u64).to_le_bytes());
+        squeezer.finalize_xof().fill(dest);
+
+        // ratchet, so the next challenge also depends on this one
+        self.write_frame(OP_SQUEEZE, label, dest);
+    }
+
+    /// Challenge `n` bits, each one true with probability 1/2
+    pub fn challenge_bits(&mut self, label: &[u8], n: usize) -> Vec<bool> {
+        let mut bytes = vec![0u8; n.div_ceil(8)];
+        self.challenge_bytes(label, &mut bytes);
+
+        (0..n).map(|i| (bytes[i / 8] >> (i % 8)) & 1 == 1).collect()
+    }
+
+    /// Challenge integer in [0, modulus)
+    ///
+    /// Twice as many bytes as the modulus are squeezed so the bias of the reduction is negligible
+    pub fn challenge_uint<const LIMBS: usize>(
+        &mut self,
+        label: &[u8],
+        modulus: &NonZero<Uint<LIMBS>>,
+    ) -> Uint<LIMBS> {
+        let mut wide = vec![0u8; LIMBS * 8 * 2];
+        self.challenge_bytes(label, &mut wide);
+        let (lower, upper) = wide.split_at(LIMBS * 8);
+
+        Uint::const_rem_wide(
+            (Uint::from_le_slice(lower), Uint::from_le_slice(upper)),
+            modulus,
+        )
+        .0
+    }
+
+    fn write_frame(&mut self, op: u8, label: &[u8], message: &[u8]) {
+        self.hasher.update(&[op]);
+        self.hasher.update(&(label.len() as u64).to_le_bytes());
+        self.hasher.update(label);
+        self.hasher.update(&(message.len() as u64).to_le_bytes());
+        self.hasher.update(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge(transcript: &mut Transcript) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        transcript.challenge_bytes(b"c", &mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_message(b"m", b"hello");
+        t2.append_message(b"m", b"hello");
+
+        assert_eq!(challenge(&mut t1), challenge(&mut t2));
+    }
+
+    #[test]
+    fn test_protocol_label_separates() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"other");
+
+        assert_ne!(challenge(&mut t1), challenge(&mut t2));
+    }
+
+    #[test]
+    fn test_framing_is_unambiguous() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_message(b"ab", b"c");
+        t2.append_message(b"a", b"bc");
+
+        assert_ne!(challenge(&mut t1), challenge(&mut t2));
+    }
+
+    #[test]
+    fn test_challenges_ratchet() {
+        let mut transcript = Transcript::new(b"test");
+
+        assert_ne!(challenge(&mut transcript), challenge(&mut transcript));
+    }
+}